@@ -0,0 +1,50 @@
+//! Compares the tokio and depth first search engines on the README's no shuffle decks.
+//!
+//! Each game tree takes a while to play so run with `cargo bench --bench engine`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use sevens::{
+    cards::Deck,
+    game::{play, play_dfs, Split, State, Stop, Strategy},
+};
+
+fn engines(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("no-shuffle");
+    group.sample_size(10);
+
+    for players in [6, 5] {
+        group.bench_with_input(
+            BenchmarkId::new("tokio", players),
+            &players,
+            |b, &players| {
+                b.iter(|| {
+                    runtime.block_on(play(
+                        State::new(players, Deck::new()),
+                        Strategy::NoConsequence,
+                        Split::default(),
+                        Stop::new(),
+                    ))
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("dfs", players), &players, |b, &players| {
+            b.iter(|| {
+                play_dfs(
+                    State::new(players, Deck::new()),
+                    Strategy::NoConsequence,
+                    Split::default(),
+                    Stop::new(),
+                )
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, engines);
+criterion_main!(benches);
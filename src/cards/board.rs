@@ -0,0 +1,197 @@
+use std::io::{stdout, IsTerminal};
+
+use colored::*;
+
+use crate::game::State;
+
+use super::{Card, CardCollection, DisplaySymbols, Rank, Suit, Symbols, SUIT_COLOUR};
+
+/// Width of each rank column in the layout
+const COLUMN_WIDTH: usize = 4;
+
+/// How a card is shown in the layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellState {
+    /// The card has been played
+    Played,
+    /// The card can be played next
+    Open,
+    /// The card can't be played yet
+    Empty,
+}
+
+/// A card's place in the layout
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutCell {
+    pub card: Card,
+    pub state: CellState,
+}
+
+impl LayoutCell {
+    /// Returns the name of the colour the card is drawn in
+    pub fn colour(&self) -> &'static str {
+        SUIT_COLOUR[self.card.suit().elem()]
+    }
+
+    /// Returns the card's rank padded to the column width
+    pub fn text(&self) -> String {
+        format!(" {:<width$}", self.card.rank(), width = COLUMN_WIDTH - 1)
+    }
+}
+
+/// Draws the cards played as the classic Sevens layout: a row for each suit with the
+/// sevens in the centre column. The cards which can be played next at either end of each
+/// row are highlighted
+pub struct BoardLayout<'a> {
+    board: &'a CardCollection,
+    coloured: bool,
+    symbols: Symbols,
+}
+
+impl<'a> BoardLayout<'a> {
+    /// Creates a layout for a board, coloured if stdout is a terminal
+    pub fn new(board: &'a CardCollection) -> Self {
+        Self {
+            board,
+            coloured: stdout().is_terminal(),
+            symbols: Symbols::Unicode,
+        }
+    }
+
+    /// Sets whether the layout is drawn in colour
+    pub fn coloured(mut self, coloured: bool) -> Self {
+        self.coloured = coloured;
+        self
+    }
+
+    /// Sets how the suit at the start of each row is written
+    pub fn symbols(mut self, symbols: Symbols) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Prints the layout with each row indented
+    pub fn print(&self, indent: &str) {
+        for row in self.rows() {
+            println!("{indent}{row}");
+        }
+    }
+
+    /// Returns the layout as a line of text for each suit
+    pub fn rows(&self) -> Vec<String> {
+        self.cells()
+            .into_iter()
+            .map(|(suit, cells)| {
+                let mut row = format!("{} ", suit.display(self.symbols));
+
+                for cell in &cells {
+                    row += &self.cell(cell);
+                }
+
+                row.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// Returns the cards of each suit's row with how each is shown, for drawing the layout
+    /// with other styling
+    pub fn cells(&self) -> Vec<(Suit, Vec<LayoutCell>)> {
+        let open = State::open_cards(self.board);
+
+        Suit::iter()
+            .map(|suit| {
+                let cells = Rank::iter()
+                    .map(|rank| {
+                        let card = Card::new_from_suit_rank(suit, rank);
+
+                        let state = if self.board.contains(card.clone()) {
+                            CellState::Played
+                        } else if open.contains(card.clone()) {
+                            CellState::Open
+                        } else {
+                            CellState::Empty
+                        };
+
+                        LayoutCell { card, state }
+                    })
+                    .collect();
+
+                (suit, cells)
+            })
+            .collect()
+    }
+
+    /// Returns the text for a single card in the layout
+    fn cell(&self, cell: &LayoutCell) -> String {
+        let text = cell.text();
+
+        if self.coloured {
+            match cell.state {
+                CellState::Played => text.color(cell.colour()).on_bright_white().to_string(),
+                CellState::Open => text.color(cell.colour()).on_yellow().to_string(),
+                CellState::Empty => text,
+            }
+        } else {
+            match cell.state {
+                CellState::Played => text,
+                CellState::Open => format!("{:<COLUMN_WIDTH$}", format!("({})", cell.card.rank())),
+                CellState::Empty => format!(" {:<width$}", ".", width = COLUMN_WIDTH - 1),
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for BoardLayout<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.rows().join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows() {
+        let board = "7♥ 6♥ 8♥ 7♣ 8♣ 9♣ 10♣".parse::<CardCollection>().unwrap();
+
+        let rows = BoardLayout::new(&board).coloured(false).rows();
+
+        assert_eq!(
+            rows,
+            vec![
+                "♥  .   .   .   .  (5)  6   7   8  (9)  .   .   .   .",
+                "♣  .   .   .   .   .  (6)  7   8   9   10 (J)  .   .",
+                "♦  .   .   .   .   .   .  (7)  .   .   .   .   .   .",
+                "♠  .   .   .   .   .   .  (7)  .   .   .   .   .   .",
+            ]
+        );
+
+        let rows = BoardLayout::new(&board)
+            .coloured(false)
+            .symbols(Symbols::Ascii)
+            .rows();
+
+        assert_eq!(
+            rows[1],
+            "C  .   .   .   .   .  (6)  7   8   9   10 (J)  .   ."
+        );
+    }
+
+    #[test]
+    fn test_cells() {
+        let board = "7♥ 6♥ 8♥".parse::<CardCollection>().unwrap();
+
+        let cells = BoardLayout::new(&board).cells();
+        let (suit, hearts) = &cells[0];
+
+        assert_eq!(*suit, Suit::Hearts);
+        assert_eq!(hearts.len(), 13);
+        assert_eq!(hearts[4].state, CellState::Open);
+        assert_eq!(hearts[5].state, CellState::Played);
+        assert_eq!(hearts[3].state, CellState::Empty);
+        assert_eq!(hearts[5].text(), " 6  ");
+        assert_eq!(hearts[5].colour(), "red");
+        assert_eq!(cells[1].1[6].colour(), "black");
+    }
+}
@@ -1,6 +1,8 @@
 use colored::*;
 
-use super::{CARD_HASH, RANKS, SUITS, SUIT_COLOUR};
+use super::{
+    DisplaySymbols, Rank, Suit, Symbols, CARD_HASH, RANKS, SUITS, SUITS_ASCII, SUIT_COLOUR,
+};
 
 #[derive(Clone, PartialEq)]
 pub struct Card(u64);
@@ -13,10 +15,75 @@ impl std::fmt::Debug for Card {
 
 impl std::fmt::Display for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&format!("{}{}", self.rank(), self.suit()))
+        self.fmt_symbols(f, Symbols::Unicode)
     }
 }
 
+impl DisplaySymbols for Card {
+    fn fmt_symbols(&self, f: &mut std::fmt::Formatter<'_>, symbols: Symbols) -> std::fmt::Result {
+        f.write_str(&format!("{}{}", self.rank(), self.suit().display(symbols)))
+    }
+}
+
+impl std::str::FromStr for Card {
+    type Err = CardParseError;
+
+    /// Parses a card such as "10♦", "10d", "TD" or "qs"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let suit_char = s.chars().last().ok_or(CardParseError::Empty)?;
+        let rank_str = &s[..s.len() - suit_char.len_utf8()];
+
+        let suit = Suit::from_char(suit_char).ok_or(CardParseError::InvalidSuit(suit_char))?;
+
+        if rank_str.is_empty() {
+            return Err(CardParseError::MissingRank);
+        }
+
+        let rank = rank_str
+            .parse::<Rank>()
+            .map_err(|_| CardParseError::InvalidRank(rank_str.to_uppercase()))?;
+
+        Ok(Self::new_from_suit_rank(suit, rank))
+    }
+}
+
+/// Errors found when parsing a card
+#[derive(Debug, Clone, PartialEq)]
+pub enum CardParseError {
+    /// Nothing to parse
+    Empty,
+    /// Suit is not valid
+    InvalidSuit(char),
+    /// No rank before the suit
+    MissingRank,
+    /// Rank is not valid
+    InvalidRank(String),
+}
+
+impl std::fmt::Display for CardParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardParseError::Empty => write!(f, "no card given"),
+            CardParseError::InvalidSuit(suit) => write!(
+                f,
+                "invalid suit '{suit}', expected one of {} or {}",
+                SUITS.iter().collect::<String>(),
+                SUITS_ASCII.iter().collect::<String>()
+            ),
+            CardParseError::MissingRank => write!(f, "no rank given"),
+            CardParseError::InvalidRank(rank) => write!(
+                f,
+                "invalid rank '{rank}', expected one of {} or T",
+                RANKS.join(" ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CardParseError {}
+
 impl Card {
     pub fn new(suit: &char, rank: &str) -> Option<Self> {
         if let Some(suit) = SUITS.iter().position(|s| s == suit) {
@@ -28,6 +95,10 @@ impl Card {
         None
     }
 
+    pub const fn new_from_suit_rank(suit: Suit, rank: Rank) -> Self {
+        Self::new_from_elems(suit.elem(), rank.elem())
+    }
+
     pub const fn new_from_elems(suit: usize, rank: usize) -> Self {
         debug_assert!(suit < SUITS.len() && rank < RANKS.len());
         Self(1 << (rank + (suit * 16)))
@@ -48,16 +119,22 @@ impl Card {
         Self(val)
     }
 
-    pub fn suit(&self) -> char {
-        SUITS[self.suit_elem() as usize]
+    pub fn suit(&self) -> Suit {
+        Suit::ALL[self.suit_elem() as usize]
     }
 
-    pub fn rank(&self) -> &str {
-        RANKS[self.rank_elem() as usize]
+    pub fn rank(&self) -> Rank {
+        Rank::ALL[self.rank_elem() as usize]
     }
 
     pub fn hash_val(&self) -> char {
-        CARD_HASH[(self.suit_elem() as usize * 13) + self.rank_elem() as usize] as char
+        CARD_HASH[self.index()] as char
+    }
+
+    /// Returns the position of the card in a new deck (0 to 51)
+    #[inline]
+    pub fn index(&self) -> usize {
+        (self.suit_elem() as usize * RANKS.len()) + self.rank_elem() as usize
     }
 
     #[inline]
@@ -87,13 +164,42 @@ impl Card {
 
     pub const CARD_COLOURED_WIDTH: usize = 5;
 
-    pub fn coloured(&self) -> ColoredString {
-        format!(" {:<2}{} ", self.rank(), self.suit())
-            .color(SUIT_COLOUR[self.suit_elem() as usize])
+    pub fn coloured(&self, symbols: Symbols) -> ColoredString {
+        format!(" {:<2}{} ", self.rank(), self.suit().display(symbols))
+            .color(SUIT_COLOUR[self.suit().elem()])
             .on_bright_white()
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Card {
+    /// Serialises as a string such as "10♦". Suit symbols are always used
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{}{}", self.rank(), self.suit().symbol()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Card {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<(Suit, Rank)> for Card {
+    fn from((suit, rank): (Suit, Rank)) -> Self {
+        Self::new_from_suit_rank(suit, rank)
+    }
+}
+
+impl From<Card> for (Suit, Rank) {
+    fn from(card: Card) -> Self {
+        (card.suit(), card.rank())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,20 +211,60 @@ mod tests {
                 let card = Card::new(suit, rank).unwrap();
 
                 assert_eq!(format!("{rank}{suit}"), format!("{card}"));
-                assert_eq!(&card.rank(), rank);
-                assert_eq!(&card.suit(), suit);
+                assert_eq!(card.rank().as_str(), *rank);
+                assert_eq!(card.suit().symbol(), *suit);
                 assert_eq!(card.rank_elem(), rno as u32);
                 assert_eq!(card.suit_elem(), sno as u32);
+                assert_eq!(card.index(), (sno * 13) + rno);
 
                 let card2 = Card::new_from_elems(sno, rno);
                 assert_eq!(card, card2);
 
                 let card3 = Card::new_from_raw(card.raw());
                 assert_eq!(card, card3);
+
+                let suit_rank: (Suit, Rank) = card.clone().into();
+                assert_eq!(suit_rank, (Suit::ALL[sno], Rank::ALL[rno]));
+                assert_eq!(Card::from(suit_rank), card);
+
+                let card4 = format!("{card}").parse::<Card>().unwrap();
+                assert_eq!(card, card4);
+
+                let card5 = format!("{rank}{}", SUITS_ASCII[sno])
+                    .parse::<Card>()
+                    .unwrap();
+                assert_eq!(card, card5);
+
+                let card6 = format!("{rank}{}", SUITS_ASCII[sno])
+                    .to_lowercase()
+                    .parse::<Card>()
+                    .unwrap();
+                assert_eq!(card, card6);
             }
         }
     }
 
+    #[test]
+    fn test_from_str() {
+        let ten_diamonds = Card::new(&'♦', "10").unwrap();
+
+        assert_eq!("10♦".parse::<Card>(), Ok(ten_diamonds.clone()));
+        assert_eq!("10D".parse::<Card>(), Ok(ten_diamonds.clone()));
+        assert_eq!("td".parse::<Card>(), Ok(ten_diamonds.clone()));
+        assert_eq!(" T♦ ".parse::<Card>(), Ok(ten_diamonds));
+        assert_eq!(
+            "a♠".parse::<Card>(),
+            Card::new(&'♠', "A").ok_or(CardParseError::Empty)
+        );
+        assert_eq!("".parse::<Card>(), Err(CardParseError::Empty));
+        assert_eq!("♠".parse::<Card>(), Err(CardParseError::MissingRank));
+        assert_eq!(
+            "1♠".parse::<Card>(),
+            Err(CardParseError::InvalidRank("1".to_string()))
+        );
+        assert_eq!("AX".parse::<Card>(), Err(CardParseError::InvalidSuit('X')));
+    }
+
     #[test]
     fn test2() {
         for sno in 0..4 {
@@ -132,4 +278,19 @@ mod tests {
             }
         }
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        for i in 0..52 {
+            let card = Card::new_from_elems(i / 13, i % 13);
+
+            let json = serde_json::to_string(&card).unwrap();
+            assert_eq!(json, format!("\"{}{}\"", card.rank(), card.suit().symbol()));
+
+            assert_eq!(serde_json::from_str::<Card>(&json).unwrap(), card);
+        }
+
+        assert!(serde_json::from_str::<Card>("\"10X\"").is_err());
+    }
 }
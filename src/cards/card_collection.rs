@@ -1,6 +1,10 @@
-use super::Card;
+use super::{Card, CardParseError, DisplaySymbols, Suit, Symbols};
 
-#[derive(Debug, Clone)]
+/// Bits which are valid cards, thirteen for each suit
+#[cfg(feature = "serde")]
+const CARD_BITS: u64 = 0x1fff_1fff_1fff_1fff;
+
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct CardCollection(u64);
 
 impl CardCollection {
@@ -32,13 +36,13 @@ impl CardCollection {
     }
 
     #[inline]
-    pub fn contains_one_to_six_except(&self, suit: u32, card: &Card) -> bool {
-        self.0 & !card.raw() & (0x3f << (suit * 16)) != 0
+    pub fn contains_one_to_six_except(&self, suit: Suit, card: &Card) -> bool {
+        self.0 & !card.raw() & (0x3f << (suit.elem() * 16)) != 0
     }
 
     #[inline]
-    pub fn contains_eight_to_king_except(&self, suit: u32, card: &Card) -> bool {
-        self.0 & !card.raw() & (0x1f80 << (suit * 16)) != 0
+    pub fn contains_eight_to_king_except(&self, suit: Suit, card: &Card) -> bool {
+        self.0 & !card.raw() & (0x1f80 << (suit.elem() * 16)) != 0
     }
 
     #[inline]
@@ -70,29 +74,35 @@ impl CardCollection {
 
 impl std::fmt::Display for CardCollection {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_symbols(f, Symbols::Unicode)
+    }
+}
+
+impl DisplaySymbols for CardCollection {
+    fn fmt_symbols(&self, f: &mut std::fmt::Formatter<'_>, symbols: Symbols) -> std::fmt::Result {
         let mut string = String::new();
 
         if f.alternate() {
-            let mut last_suit = ' ';
+            let mut last_suit = None;
 
             for c in self.card_iterator() {
                 let suit = c.suit();
                 let rank = c.rank();
 
-                if suit != last_suit {
-                    if last_suit != ' ' {
+                if Some(suit) != last_suit {
+                    if last_suit.is_some() {
                         string += "] ";
                     }
 
-                    string += &format!("{suit} [{rank}");
+                    string += &format!("{} [{rank}", suit.display(symbols));
 
-                    last_suit = suit;
+                    last_suit = Some(suit);
                 } else {
                     string += &format!(" {rank}");
                 }
             }
 
-            if last_suit != ' ' {
+            if last_suit.is_some() {
                 string += "] ";
             }
         } else {
@@ -101,7 +111,7 @@ impl std::fmt::Display for CardCollection {
                     string += " ";
                 }
 
-                string += &format!("{c}");
+                string += &format!("{}", c.display(symbols));
             }
         }
 
@@ -109,6 +119,95 @@ impl std::fmt::Display for CardCollection {
     }
 }
 
+impl std::str::FromStr for CardCollection {
+    type Err = CardCollectionParseError;
+
+    /// Parses a list of cards separated by spaces or commas
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut collection = Self::new();
+
+        for (position, elem) in s
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|e| !e.is_empty())
+            .enumerate()
+        {
+            let card =
+                elem.parse::<Card>()
+                    .map_err(|error| CardCollectionParseError::InvalidCard {
+                        position: position + 1,
+                        text: elem.to_string(),
+                        error,
+                    })?;
+
+            if collection.contains(card.clone()) {
+                return Err(CardCollectionParseError::Duplicate(card));
+            }
+
+            collection.add(card);
+        }
+
+        Ok(collection)
+    }
+}
+
+/// Errors found when parsing a list of cards
+#[derive(Debug, Clone, PartialEq)]
+pub enum CardCollectionParseError {
+    /// Card in the list is not valid
+    InvalidCard {
+        position: usize,
+        text: String,
+        error: CardParseError,
+    },
+    /// Card appears more than once
+    Duplicate(Card),
+}
+
+impl std::fmt::Display for CardCollectionParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardCollectionParseError::InvalidCard {
+                position,
+                text,
+                error,
+            } => write!(f, "card {position} ('{text}'): {error}"),
+            CardCollectionParseError::Duplicate(card) => {
+                write!(f, "{card} appears more than once")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardCollectionParseError {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CardCollection {
+    /// Serialises as a list of cards. Bits outside the cards, such as the moves past an ace
+    /// or king left in a state's valid moves, are dropped
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(CardCollection(self.0 & CARD_BITS).card_iterator())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CardCollection {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut collection = CardCollection::new();
+
+        for card in Vec::<Card>::deserialize(deserializer)? {
+            if collection.contains(card.clone()) {
+                return Err(serde::de::Error::custom(
+                    CardCollectionParseError::Duplicate(card),
+                ));
+            }
+
+            collection.add(card);
+        }
+
+        Ok(collection)
+    }
+}
+
 pub struct CardCollectionIterator(i64);
 
 impl Iterator for CardCollectionIterator {
@@ -153,14 +252,14 @@ mod tests {
 
         // Test contains A-6 and 8-K
         println!("{:x} {:x}", collection.raw(), card2.raw());
-        assert!(collection.contains_one_to_six_except(0, &card2));
-        assert!(!collection.contains_eight_to_king_except(0, &card2));
-        assert!(!collection.contains_one_to_six_except(1, &card2));
-        assert!(!collection.contains_eight_to_king_except(1, &card2));
-        assert!(!collection.contains_one_to_six_except(2, &card2));
-        assert!(collection.contains_eight_to_king_except(2, &card2));
-        assert!(!collection.contains_one_to_six_except(3, &card2));
-        assert!(collection.contains_eight_to_king_except(3, &card2));
+        assert!(collection.contains_one_to_six_except(Suit::Hearts, &card2));
+        assert!(!collection.contains_eight_to_king_except(Suit::Hearts, &card2));
+        assert!(!collection.contains_one_to_six_except(Suit::Clubs, &card2));
+        assert!(!collection.contains_eight_to_king_except(Suit::Clubs, &card2));
+        assert!(!collection.contains_one_to_six_except(Suit::Diamonds, &card2));
+        assert!(collection.contains_eight_to_king_except(Suit::Diamonds, &card2));
+        assert!(!collection.contains_one_to_six_except(Suit::Spades, &card2));
+        assert!(collection.contains_eight_to_king_except(Suit::Spades, &card2));
 
         // Test contains
         let mut deck = Deck::new();
@@ -206,4 +305,68 @@ mod tests {
             assert!(!collection.contains(card.clone()));
         }
     }
+
+    #[test]
+    fn test_from_str() {
+        let collection = "A♥ 7c, 10D,QS".parse::<CardCollection>().unwrap();
+
+        assert_eq!(
+            collection
+                .card_iterator()
+                .map(|c| format!("{c}"))
+                .collect::<Vec<_>>(),
+            vec!["A♥", "7♣", "10♦", "Q♠"]
+        );
+
+        assert_eq!(
+            format!("{collection}").parse::<CardCollection>(),
+            Ok(collection)
+        );
+
+        assert_eq!("".parse::<CardCollection>(), Ok(CardCollection::new()));
+
+        assert_eq!(
+            "A♥ 7♣ 7C".parse::<CardCollection>(),
+            Err(CardCollectionParseError::Duplicate(
+                Card::new(&'♣', "7").unwrap()
+            ))
+        );
+
+        assert_eq!(
+            "A♥ 7♣ 1C".parse::<CardCollection>(),
+            Err(CardCollectionParseError::InvalidCard {
+                position: 3,
+                text: "1C".to_string(),
+                error: CardParseError::InvalidRank("1".to_string())
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut deck = Deck::new();
+        deck.shuffle();
+
+        let collection = deck
+            .iter()
+            .take(20)
+            .fold(CardCollection::new(), |mut c, card| {
+                c.add(card.clone());
+                c
+            });
+
+        let json = serde_json::to_string(&collection).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CardCollection>(&json).unwrap(),
+            collection
+        );
+
+        assert_eq!(
+            serde_json::to_string(&"A♥ 10♦".parse::<CardCollection>().unwrap()).unwrap(),
+            "[\"A♥\",\"10♦\"]"
+        );
+
+        assert!(serde_json::from_str::<CardCollection>("[\"A♥\",\"AH\"]").is_err());
+    }
 }
@@ -0,0 +1,326 @@
+use super::{Card, CardCollection, Deck, BASE62};
+
+/// Canonical identifier for a deal, being the partition of the deck in to each player's hand.
+/// Every deck order which deals the same hands has the same deal ID for a given player count
+#[derive(Debug, Clone, PartialEq)]
+pub struct DealId {
+    /// Number of players
+    players: u8,
+    /// Rank of the deal amongst all possible deals
+    rank: BigUint,
+}
+
+impl DealId {
+    /// Creates the deal ID for the hands dealt from a deck
+    pub fn new_from_deck(deck: &Deck, players: u8) -> Self {
+        let mut hands = vec![CardCollection::new(); players as usize];
+
+        for (i, c) in deck.iter().enumerate() {
+            hands[i % players as usize].add(c.clone());
+        }
+
+        Self::new_from_hands(&hands)
+    }
+
+    /// Creates the deal ID for a set of hands. The hands must be a valid deal
+    pub fn new_from_hands(hands: &[CardCollection]) -> Self {
+        let players = hands.len();
+
+        // Remaining cards in deck order
+        let mut remaining = (0..52)
+            .map(|i| Card::new_from_elems(i / 13, i % 13))
+            .collect::<Vec<_>>();
+
+        // Rank the subset chosen by each player from the remaining cards
+        let mut ranks = Vec::with_capacity(players);
+
+        for (player, hand) in hands.iter().enumerate() {
+            let size = hand_size(players, player);
+            let mut rank = 0;
+            let mut chosen = 0;
+
+            for (pos, c) in remaining.iter().enumerate() {
+                if hand.contains(c.clone()) {
+                    chosen += 1;
+                    rank += binomial(pos, chosen);
+                }
+            }
+
+            debug_assert_eq!(chosen, size);
+
+            ranks.push((rank, binomial(remaining.len(), size)));
+
+            remaining.retain(|c| !hand.contains(c.clone()));
+        }
+
+        // Combine the ranks as a mixed radix number with the first player least significant
+        let rank = ranks
+            .iter()
+            .rev()
+            .fold(BigUint::default(), |mut rank, (r, radix)| {
+                rank.mul_add(*radix, *r);
+                rank
+            });
+
+        Self {
+            players: players as u8,
+            rank,
+        }
+    }
+
+    /// Parses a deal ID for a number of players
+    pub fn parse(id: &str, players: u8) -> Result<Self, String> {
+        if !(2..=52).contains(&players) {
+            return Err(format!("Invalid number of players {players}"));
+        }
+
+        let mut rank = BigUint::default();
+
+        for c in id.chars() {
+            let digit = BASE62
+                .iter()
+                .position(|b| *b as char == c)
+                .ok_or_else(|| format!("Invalid character '{c}' in deal ID"))?;
+
+            rank.mul_add(62, digit as u64);
+        }
+
+        let deal_id = Self { players, rank };
+
+        if deal_id.rank >= deal_id.deals() {
+            return Err(format!(
+                "Deal ID {id} is out of range for {players} players"
+            ));
+        }
+
+        Ok(deal_id)
+    }
+
+    /// Returns the hands for the deal
+    pub fn hands(&self) -> Vec<CardCollection> {
+        let players = self.players as usize;
+
+        let mut remaining = (0..52)
+            .map(|i| Card::new_from_elems(i / 13, i % 13))
+            .collect::<Vec<_>>();
+
+        let mut rank = self.rank.clone();
+        let mut hands = Vec::with_capacity(players);
+
+        for player in 0..players {
+            let size = hand_size(players, player);
+            let mut r = rank.div_rem(binomial(remaining.len(), size));
+
+            // Unrank the subset
+            let mut hand = CardCollection::new();
+            let mut pos = remaining.len();
+
+            for chosen in (1..=size).rev() {
+                pos -= 1;
+
+                while binomial(pos, chosen) > r {
+                    pos -= 1;
+                }
+
+                r -= binomial(pos, chosen);
+                hand.add(remaining[pos].clone());
+            }
+
+            remaining.retain(|c| !hand.contains(c.clone()));
+            hands.push(hand);
+        }
+
+        hands
+    }
+
+    /// Returns a deck which deals the hands for the deal
+    pub fn deck(&self) -> Deck {
+        Deck::new_from_hands(&self.hands()).expect("Deal ID hands are not valid")
+    }
+
+    /// Returns the number of possible deals for the number of players
+    fn deals(&self) -> BigUint {
+        let players = self.players as usize;
+        let mut remaining = 52;
+
+        let mut deals = BigUint::default();
+        deals.mul_add(0, 1);
+
+        for player in 0..players {
+            let size = hand_size(players, player);
+            deals.mul_add(binomial(remaining, size), 0);
+            remaining -= size;
+        }
+
+        deals
+    }
+}
+
+impl std::fmt::Display for DealId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rank = self.rank.clone();
+        let mut digits = Vec::new();
+
+        loop {
+            digits.push(BASE62[rank.div_rem(62) as usize]);
+
+            if rank.is_zero() {
+                break;
+            }
+        }
+
+        digits.reverse();
+
+        f.write_str(&String::from_utf8(digits).unwrap())
+    }
+}
+
+/// Returns the number of cards dealt to a player
+fn hand_size(players: usize, player: usize) -> usize {
+    (52 / players) + usize::from(player < 52 % players)
+}
+
+/// Returns the binomial coefficient n choose k (n <= 52)
+fn binomial(n: usize, k: usize) -> u64 {
+    if k > n {
+        return 0;
+    }
+
+    let k = k.min(n - k);
+
+    (0..k).fold(1u64, |acc, i| acc * (n - i) as u64 / (i + 1) as u64)
+}
+
+/// Minimal arbitrary precision unsigned integer, least significant limb first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct BigUint(Vec<u64>);
+
+impl BigUint {
+    /// Sets self to self * mul + add
+    fn mul_add(&mut self, mul: u64, add: u64) {
+        let mut carry = add as u128;
+
+        for limb in self.0.iter_mut() {
+            let val = (*limb as u128 * mul as u128) + carry;
+            *limb = val as u64;
+            carry = val >> 64;
+        }
+
+        if carry != 0 {
+            self.0.push(carry as u64);
+        }
+
+        self.normalise();
+    }
+
+    /// Divides self by div returning the remainder
+    fn div_rem(&mut self, div: u64) -> u64 {
+        let mut rem = 0u128;
+
+        for limb in self.0.iter_mut().rev() {
+            let val = (rem << 64) | *limb as u128;
+            *limb = (val / div as u128) as u64;
+            rem = val % div as u128;
+        }
+
+        self.normalise();
+
+        rem as u64
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn normalise(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for players in 2..=52 {
+            let mut deck = Deck::new();
+            deck.shuffle();
+
+            let deal_id = DealId::new_from_deck(&deck, players);
+            let string = format!("{deal_id}");
+
+            let deal_id2 = DealId::parse(&string, players).unwrap();
+            assert_eq!(deal_id, deal_id2);
+
+            // The canonical deck deals the same hands
+            let deck2 = deal_id2.deck();
+            assert_eq!(DealId::new_from_deck(&deck2, players), deal_id);
+            assert_eq!(deck2.hash_string(), deal_id.deck().hash_string());
+
+            let mut hands = vec![CardCollection::new(); players as usize];
+
+            for (i, c) in deck.iter().enumerate() {
+                hands[i % players as usize].add(c.clone());
+            }
+
+            assert_eq!(deal_id2.hands(), hands);
+        }
+    }
+
+    #[test]
+    fn test_equivalent_decks() {
+        // Swapping cards within a hand gives the same deal
+        let deck =
+            Deck::new_from_hash("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz").unwrap();
+        let deck2 =
+            Deck::new_from_hash("EBCDAFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz").unwrap();
+        let deck3 =
+            Deck::new_from_hash("BACDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz").unwrap();
+
+        let deal_id = DealId::new_from_deck(&deck, 4);
+
+        assert_eq!(DealId::new_from_deck(&deck2, 4), deal_id);
+        assert_ne!(DealId::new_from_deck(&deck3, 4), deal_id);
+    }
+
+    #[test]
+    fn test_range() {
+        // The first deal gives the first cards in deck order to the first player
+        let first = DealId::parse("0", 4).unwrap();
+        assert_eq!(format!("{first}"), "0");
+        assert_eq!(
+            first.hands()[0],
+            "AH 2H 3H 4H 5H 6H 7H 8H 9H 10H JH QH KH"
+                .parse::<CardCollection>()
+                .unwrap()
+        );
+
+        // One past the last deal is out of range
+        let past_last = DealId {
+            players: 4,
+            rank: first.deals(),
+        };
+        assert!(DealId::parse(&format!("{past_last}"), 4).is_err());
+
+        assert!(DealId::parse("!", 4).is_err());
+        assert!(DealId::parse("0", 1).is_err());
+    }
+}
@@ -2,7 +2,11 @@ use std::collections::{vec_deque::Iter, VecDeque};
 
 use rand::Rng;
 
-use super::{Card, CARD_HASH, RANKS, SUITS};
+use super::{Card, CardCollection, CardParseError, DisplaySymbols, Symbols, BASE62, RANKS, SUITS};
+
+/// Modulus of the deck hash checksum. Prime and larger than the hash length so any single
+/// changed character or swapped pair of characters changes the checksum
+const CHECKSUM_MOD: usize = 61;
 
 #[derive(Debug)]
 pub struct Deck(VecDeque<Card>);
@@ -17,36 +21,129 @@ impl Deck {
         }))
     }
 
-    pub fn new_from_hash(hash: &str) -> Option<Self> {
-        let mut touched = [false; 52];
+    /// Creates a deck from a hash, with an optional trailing checksum character
+    pub fn new_from_hash(hash: &str) -> Result<Self, DeckHashError> {
+        let chars = hash.chars().collect::<Vec<_>>();
 
-        if let Some(cards) = hash
-            .chars()
-            .map(|c| match CARD_HASH.iter().position(|h| *h as char == c) {
-                None => None,
-                Some(p) => {
-                    touched[p] = true;
-                    Card::new_from_hash(c as u8)
-                }
-            })
-            .collect()
-        {
-            if touched.into_iter().any(|t| !t) {
-                // Not all cards specified
-                None
-            } else {
-                Some(Self(cards))
+        let (body, checksum) = match chars.len() {
+            53 => (&chars[..52], Some(chars[52])),
+            len if len > 53 => return Err(DeckHashError::Length(len)),
+            _ => (&chars[..], None),
+        };
+
+        let mut seen = CardCollection::new();
+        let mut cards = VecDeque::new();
+
+        for (position, c) in body.iter().enumerate() {
+            let card = u8::try_from(*c).ok().and_then(Card::new_from_hash).ok_or(
+                DeckHashError::InvalidChar {
+                    position: position + 1,
+                    char: *c,
+                },
+            )?;
+
+            if seen.contains(card.clone()) {
+                return Err(DeckHashError::Duplicate {
+                    position: position + 1,
+                    card,
+                });
             }
-        } else {
-            // Invalid character
-            None
+
+            seen.add(card.clone());
+            cards.push_back(card);
         }
+
+        if cards.len() != 52 {
+            let missing = Self::new()
+                .0
+                .into_iter()
+                .filter(|c| !seen.contains(c.clone()))
+                .fold(CardCollection::new(), |mut missing, c| {
+                    missing.add(c);
+                    missing
+                });
+
+            return Err(DeckHashError::Missing(missing));
+        }
+
+        let deck = Self(cards);
+
+        if let Some(found) = checksum {
+            let expected = deck.checksum();
+
+            if found != expected {
+                return Err(DeckHashError::Checksum { expected, found });
+            }
+        }
+
+        Ok(deck)
+    }
+
+    /// Creates a deck which deals the given hands when dealt round the players in turn
+    pub fn new_from_hands(hands: &[CardCollection]) -> Result<Self, String> {
+        let players = hands.len();
+
+        if players < 2 {
+            return Err(format!("At least 2 hands are required but {players} given"));
+        }
+
+        // Check the hands partition the deck
+        let mut all = CardCollection::new();
+
+        for hand in hands {
+            let duplicates = CardCollection::new_from_raw(all.raw() & hand.raw());
+
+            if !duplicates.is_empty() {
+                return Err(format!("Cards in more than one hand: {duplicates}"));
+            }
+
+            all = CardCollection::new_from_raw(all.raw() | hand.raw());
+        }
+
+        let missing = Self::new()
+            .0
+            .into_iter()
+            .filter(|c| !all.contains(c.clone()))
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            return Err(format!(
+                "Cards not in any hand: {}",
+                CardCollection::new_from_raw(missing.iter().fold(0, |acc, c| acc | c.raw()))
+            ));
+        }
+
+        // Check the hand sizes can be dealt
+        for (player, hand) in hands.iter().enumerate() {
+            let expected = (52 / players) + usize::from(player < 52 % players);
+
+            if hand.len() != expected {
+                return Err(format!(
+                    "Player {} has {} cards but {expected} are dealt to them with {players} players",
+                    player + 1,
+                    hand.len()
+                ));
+            }
+        }
+
+        // Interleave the hands
+        let mut iters = hands.iter().map(|h| h.card_iterator()).collect::<Vec<_>>();
+
+        Ok(Self(
+            (0..52)
+                .map(|i| iters[i % players].next().unwrap())
+                .collect(),
+        ))
     }
 
     pub fn shuffle(&mut self) {
-        let mut shuffled = VecDeque::new();
+        self.shuffle_with(&mut rand::thread_rng());
+    }
 
-        let mut rng = rand::thread_rng();
+    /// Shuffles the deck with a given random number generator, so a seeded generator
+    /// always gives the same deck
+    pub fn shuffle_with(&mut self, rng: &mut impl Rng) {
+        let mut shuffled = VecDeque::new();
 
         while !self.0.is_empty() {
             let elem = rng.gen_range(0..self.0.len());
@@ -65,13 +162,44 @@ impl Deck {
         self.0.iter().map(|c| c.hash_val()).collect()
     }
 
-    pub fn iter(&self) -> Iter<Card> {
+    /// Returns the deck hash with a trailing checksum character
+    pub fn hash_string_checksummed(&self) -> String {
+        let mut hash = self.hash_string();
+        hash.push(self.checksum());
+        hash
+    }
+
+    /// Calculates the checksum character for the deck hash
+    fn checksum(&self) -> char {
+        let sum = self
+            .0
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i + 1) * (c.index() + 1))
+            .sum::<usize>();
+
+        BASE62[sum % CHECKSUM_MOD] as char
+    }
+
+    pub fn iter(&self) -> Iter<'_, Card> {
         self.0.iter()
     }
 }
 
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl std::fmt::Display for Deck {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_symbols(f, Symbols::Unicode)
+    }
+}
+
+impl DisplaySymbols for Deck {
+    fn fmt_symbols(&self, f: &mut std::fmt::Formatter<'_>, symbols: Symbols) -> std::fmt::Result {
         let mut string = String::new();
 
         for (i, c) in self.0.iter().enumerate() {
@@ -79,13 +207,152 @@ impl std::fmt::Display for Deck {
                 string += " ";
             }
 
-            string += &format!("{c}");
+            string += &format!("{}", c.display(symbols));
         }
 
         f.write_str(&string)
     }
 }
 
+impl std::str::FromStr for Deck {
+    type Err = DeckParseError;
+
+    /// Parses a list of all 52 cards separated by spaces or commas
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = s
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|e| !e.is_empty())
+            .enumerate()
+            .map(|(position, elem)| {
+                elem.parse::<Card>()
+                    .map_err(|error| DeckParseError::InvalidCard {
+                        position: position + 1,
+                        text: elem.to_string(),
+                        error,
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::new_from_cards(cards)
+    }
+}
+
+impl Deck {
+    /// Creates a deck from a list of all 52 cards
+    fn new_from_cards(cards: Vec<Card>) -> Result<Self, DeckParseError> {
+        let mut seen = CardCollection::new();
+
+        for card in &cards {
+            if seen.contains(card.clone()) {
+                return Err(DeckParseError::Duplicate(card.clone()));
+            }
+
+            seen.add(card.clone());
+        }
+
+        if cards.len() != 52 {
+            let missing = Self::new()
+                .0
+                .into_iter()
+                .filter(|c| !seen.contains(c.clone()))
+                .fold(CardCollection::new(), |mut missing, c| {
+                    missing.add(c);
+                    missing
+                });
+
+            return Err(DeckParseError::Missing(missing));
+        }
+
+        Ok(Self(cards.into()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Deck {
+    /// Serialises as a list of cards
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Deck {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new_from_cards(Vec::<Card>::deserialize(deserializer)?)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Errors found when parsing a deck
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeckParseError {
+    /// Card in the list is not valid
+    InvalidCard {
+        position: usize,
+        text: String,
+        error: CardParseError,
+    },
+    /// Card appears more than once
+    Duplicate(Card),
+    /// Cards not in the deck
+    Missing(CardCollection),
+}
+
+impl std::fmt::Display for DeckParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckParseError::InvalidCard {
+                position,
+                text,
+                error,
+            } => write!(f, "card {position} ('{text}'): {error}"),
+            DeckParseError::Duplicate(card) => write!(f, "{card} appears more than once"),
+            DeckParseError::Missing(cards) => write!(f, "cards missing from the deck: {cards}"),
+        }
+    }
+}
+
+impl std::error::Error for DeckParseError {}
+
+/// Errors found when creating a deck from a hash
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeckHashError {
+    /// Character is not a card
+    InvalidChar { position: usize, char: char },
+    /// Card appears more than once
+    Duplicate { position: usize, card: Card },
+    /// Cards not in the hash
+    Missing(CardCollection),
+    /// Hash is too long
+    Length(usize),
+    /// Checksum character doesn't match the cards
+    Checksum { expected: char, found: char },
+}
+
+impl std::fmt::Display for DeckHashError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckHashError::InvalidChar { position, char } => {
+                write!(f, "character {position} ('{char}') is not a card")
+            }
+            DeckHashError::Duplicate { position, card } => {
+                write!(f, "character {position} is {card} which appears more than once")
+            }
+            DeckHashError::Missing(cards) => write!(f, "cards missing from the hash: {cards}"),
+            DeckHashError::Length(len) => write!(
+                f,
+                "hash has {len} characters but should have 52, or 53 with a checksum"
+            ),
+            DeckHashError::Checksum { expected, found } => write!(
+                f,
+                "checksum '{found}' doesn't match the cards (expected '{expected}'), check for typos"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DeckHashError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,4 +385,180 @@ mod tests {
 
         assert!(expected.is_empty());
     }
+
+    #[test]
+    fn test_from_hands() {
+        let hands = vec![
+            CardCollection::new_from_raw(0x1fff_0000_1fff),
+            CardCollection::new_from_raw(0x1fff_0000_1fff_0000),
+        ];
+
+        let deck = Deck::new_from_hands(&hands).unwrap();
+
+        // Deal the deck
+        let mut dealt = vec![CardCollection::new(), CardCollection::new()];
+
+        for (i, c) in deck.iter().enumerate() {
+            dealt[i % 2].add(c.clone());
+        }
+
+        assert_eq!(dealt, hands);
+
+        // Duplicate cards
+        let hands = vec![
+            CardCollection::new_from_raw(0x1fff_0000_1fff),
+            CardCollection::new_from_raw(0x1fff_0000_1fff_0001),
+        ];
+
+        assert!(Deck::new_from_hands(&hands).is_err());
+
+        // Missing cards
+        let hands = vec![
+            CardCollection::new_from_raw(0x1fff_0000_1fff),
+            CardCollection::new_from_raw(0x1fff_0000_1ffe_0000),
+        ];
+
+        assert!(Deck::new_from_hands(&hands).is_err());
+
+        // Wrong hand sizes
+        let hands = vec![
+            CardCollection::new_from_raw(0x1fff_0000_1fff_1fff),
+            CardCollection::new_from_raw(0x1fff_0000_0000),
+        ];
+
+        assert!(Deck::new_from_hands(&hands).is_err());
+    }
+
+    #[test]
+    fn test_shuffle_with() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let shuffled = |seed| {
+            let mut deck = Deck::new();
+            deck.shuffle_with(&mut StdRng::seed_from_u64(seed));
+            deck.hash_string()
+        };
+
+        // Same seed gives the same deck
+        assert_eq!(shuffled(1), shuffled(1));
+        assert_ne!(shuffled(1), shuffled(2));
+        assert_ne!(shuffled(1), Deck::new().hash_string());
+    }
+
+    #[test]
+    fn test_from_str() {
+        let mut deck = Deck::new();
+        deck.shuffle();
+
+        // Round trip
+        let deck2 = format!("{deck}").parse::<Deck>().unwrap();
+        assert_eq!(deck.0, deck2.0);
+
+        // ASCII suits
+        let string = format!("{deck}")
+            .replace('♥', "h")
+            .replace('♣', "C")
+            .replace('♦', "d")
+            .replace('♠', "S");
+
+        let deck2 = string.parse::<Deck>().unwrap();
+        assert_eq!(deck.0, deck2.0);
+
+        // Displayed with ASCII suits
+        let string = deck.display(Symbols::Ascii).to_string();
+        assert!(string.is_ascii());
+        assert_eq!(string.parse::<Deck>().unwrap().0, deck.0);
+
+        // Missing card
+        let string = format!("{deck}").replace("10♥", "");
+
+        assert_eq!(
+            string.parse::<Deck>().unwrap_err(),
+            DeckParseError::Missing(CardCollection::new_single(Card::new(&'♥', "10").unwrap()))
+        );
+
+        // Duplicate card
+        let string = format!("{deck}").replace("10♥", "J♥");
+
+        assert_eq!(
+            string.parse::<Deck>().unwrap_err(),
+            DeckParseError::Duplicate(Card::new(&'♥', "J").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_from_hash() {
+        let mut deck = Deck::new();
+        deck.shuffle();
+
+        // Round trip with and without a checksum
+        let hash = deck.hash_string();
+        let checksummed = deck.hash_string_checksummed();
+
+        assert_eq!(checksummed.len(), 53);
+        assert_eq!(Deck::new_from_hash(&hash).unwrap().0, deck.0);
+        assert_eq!(Deck::new_from_hash(&checksummed).unwrap().0, deck.0);
+
+        // Invalid character
+        let mut bad = hash.clone();
+        bad.replace_range(3..4, "!");
+
+        assert_eq!(
+            Deck::new_from_hash(&bad).unwrap_err(),
+            DeckHashError::InvalidChar {
+                position: 4,
+                char: '!'
+            }
+        );
+
+        // Duplicate and missing cards
+        let first = &hash[0..1];
+        let mut bad = hash.clone();
+        bad.replace_range(10..11, first);
+
+        assert_eq!(
+            Deck::new_from_hash(&bad).unwrap_err(),
+            DeckHashError::Duplicate {
+                position: 11,
+                card: deck.0[0].clone()
+            }
+        );
+
+        assert_eq!(
+            Deck::new_from_hash(&hash[1..]).unwrap_err(),
+            DeckHashError::Missing(CardCollection::new_single(deck.0[0].clone()))
+        );
+
+        // Too long
+        assert_eq!(
+            Deck::new_from_hash(&format!("{checksummed}A")).unwrap_err(),
+            DeckHashError::Length(54)
+        );
+
+        // Every swap of two different cards changes the checksum
+        for i in 0..52 {
+            for j in (i + 1)..52 {
+                let mut swapped = checksummed.clone().into_bytes();
+                swapped.swap(i, j);
+
+                assert!(matches!(
+                    Deck::new_from_hash(&String::from_utf8(swapped).unwrap()),
+                    Err(DeckHashError::Checksum { .. })
+                ));
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut deck = Deck::new();
+        deck.shuffle();
+
+        let json = serde_json::to_string(&deck).unwrap();
+        assert_eq!(serde_json::from_str::<Deck>(&json).unwrap().0, deck.0);
+
+        // Incomplete deck
+        assert!(serde_json::from_str::<Deck>("[\"A♥\"]").is_err());
+    }
 }
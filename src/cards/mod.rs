@@ -1,15 +1,29 @@
+//! Cards, collections of cards and decks
+
+mod board;
 mod card;
 mod card_collection;
+mod deal_id;
 mod deck;
 mod print;
+mod rank;
+mod suit;
+mod symbols;
 
-pub use card::Card;
-pub use card_collection::CardCollection;
-pub use deck::Deck;
+pub use board::{BoardLayout, CellState, LayoutCell};
+pub use card::{Card, CardParseError};
+pub use card_collection::{CardCollection, CardCollectionParseError};
+pub use deal_id::DealId;
+pub use deck::{Deck, DeckHashError, DeckParseError};
 pub use print::CardIterPrint;
+pub use rank::Rank;
+pub use suit::Suit;
+pub use symbols::{DisplaySymbols, Symbols, WithSymbols};
 
 pub const SUITS: [char; 4] = ['♥', '♣', '♦', '♠'];
 
+pub const SUITS_ASCII: [char; 4] = ['H', 'C', 'D', 'S'];
+
 pub const RANKS: [&str; 13] = [
     "A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K",
 ];
@@ -17,3 +31,6 @@ pub const RANKS: [&str; 13] = [
 const SUIT_COLOUR: [&str; 4] = ["red", "black", "red", "black"];
 
 const CARD_HASH: &[u8; 52] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Characters used for base-62 numbers
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
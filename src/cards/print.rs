@@ -2,10 +2,10 @@ use std::io::{stdout, IsTerminal};
 
 use terminal_size::Width;
 
-use super::Card;
+use super::{Card, DisplaySymbols, Symbols};
 
 pub trait CardIterPrint: Iterator<Item = Card> {
-    fn print(&mut self, title: &str) {
+    fn print(&mut self, title: &str, symbols: Symbols) {
         let title_len = title.chars().count();
         let mut format = None;
 
@@ -18,29 +18,29 @@ pub trait CardIterPrint: Iterator<Item = Card> {
         }
 
         if let Some(width) = format {
-            self.print_formatted(title, width as usize);
+            self.print_formatted(title, width as usize, symbols);
         } else {
-            self.print_plain(title);
+            self.print_plain(title, symbols);
         }
     }
 
-    fn print_plain(&mut self, title: &str) {
+    fn print_plain(&mut self, title: &str, symbols: Symbols) {
         print!("{title}");
 
         for c in self {
-            print!(" {c}");
+            print!(" {}", c.display(symbols));
         }
         println!();
     }
 
-    fn print_formatted(&mut self, title: &str, width: usize) {
+    fn print_formatted(&mut self, title: &str, width: usize, symbols: Symbols) {
         let title_len = title.chars().count();
 
         print!("{title}");
         let mut cur_width = title_len;
 
         for c in self {
-            let next = c.coloured();
+            let next = c.coloured(symbols);
 
             if cur_width + 1 + Card::CARD_COLOURED_WIDTH > width {
                 println!();
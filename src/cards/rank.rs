@@ -0,0 +1,121 @@
+use super::RANKS;
+
+/// Card rank
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rank {
+    Ace,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+}
+
+impl Rank {
+    /// All ranks from ace to king
+    pub const ALL: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+
+    /// Returns an iterator over all ranks from ace to king
+    pub fn iter() -> impl Iterator<Item = Rank> {
+        Self::ALL.into_iter()
+    }
+
+    /// Returns the rank for an element number (0 to 12)
+    pub fn from_elem(elem: usize) -> Option<Self> {
+        Self::ALL.get(elem).cloned()
+    }
+
+    /// Returns the element number of the rank (0 to 12)
+    #[inline]
+    pub const fn elem(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the rank one higher, if any
+    pub fn higher(self) -> Option<Self> {
+        Self::from_elem(self.elem() + 1)
+    }
+
+    /// Returns the rank one lower, if any
+    pub fn lower(self) -> Option<Self> {
+        self.elem().checked_sub(1).and_then(Self::from_elem)
+    }
+
+    /// Returns the rank as displayed
+    pub fn as_str(self) -> &'static str {
+        RANKS[self.elem()]
+    }
+}
+
+impl std::fmt::Display for Rank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Rank {
+    type Err = ();
+
+    /// Parses a rank in upper or lower case. T is accepted for 10
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_uppercase();
+
+        if s == "T" {
+            return Ok(Rank::Ten);
+        }
+
+        RANKS
+            .iter()
+            .position(|r| *r == s)
+            .map(|elem| Self::ALL[elem])
+            .ok_or(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test1() {
+        assert_eq!(Rank::iter().count(), 13);
+
+        for (elem, rank) in Rank::iter().enumerate() {
+            assert_eq!(rank.elem(), elem);
+            assert_eq!(Rank::from_elem(elem), Some(rank));
+            assert_eq!(format!("{rank}"), RANKS[elem]);
+            assert_eq!(RANKS[elem].parse::<Rank>(), Ok(rank));
+            assert_eq!(RANKS[elem].to_lowercase().parse::<Rank>(), Ok(rank));
+        }
+
+        assert_eq!("t".parse::<Rank>(), Ok(Rank::Ten));
+        assert_eq!("1".parse::<Rank>(), Err(()));
+        assert_eq!(Rank::from_elem(13), None);
+        assert_eq!(Rank::Ace.lower(), None);
+        assert_eq!(Rank::Ace.higher(), Some(Rank::Two));
+        assert_eq!(Rank::King.higher(), None);
+        assert!(Rank::Six < Rank::Seven);
+        assert_eq!(format!("{:<2}|", Rank::Ace), "A |");
+    }
+}
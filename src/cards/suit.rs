@@ -0,0 +1,97 @@
+use super::{DisplaySymbols, Symbols, SUITS, SUITS_ASCII};
+
+/// Card suit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Suit {
+    Hearts,
+    Clubs,
+    Diamonds,
+    Spades,
+}
+
+impl Suit {
+    /// All suits in deck order
+    pub const ALL: [Suit; 4] = [Suit::Hearts, Suit::Clubs, Suit::Diamonds, Suit::Spades];
+
+    /// Returns an iterator over all suits in deck order
+    pub fn iter() -> impl Iterator<Item = Suit> {
+        Self::ALL.into_iter()
+    }
+
+    /// Returns the suit for an element number (0 to 3)
+    pub fn from_elem(elem: usize) -> Option<Self> {
+        Self::ALL.get(elem).cloned()
+    }
+
+    /// Returns the suit for a symbol or ASCII letter (upper or lower case)
+    pub fn from_char(c: char) -> Option<Self> {
+        SUITS
+            .iter()
+            .position(|s| *s == c)
+            .or_else(|| {
+                SUITS_ASCII
+                    .iter()
+                    .position(|s| *s == c.to_ascii_uppercase())
+            })
+            .map(|elem| Self::ALL[elem])
+    }
+
+    /// Returns the element number of the suit (0 to 3)
+    #[inline]
+    pub const fn elem(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the suit symbol
+    pub fn symbol(self) -> char {
+        SUITS[self.elem()]
+    }
+
+    /// Returns the suit ASCII letter
+    pub fn letter(self) -> char {
+        SUITS_ASCII[self.elem()]
+    }
+}
+
+impl std::fmt::Display for Suit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_symbols(f, Symbols::Unicode)
+    }
+}
+
+impl DisplaySymbols for Suit {
+    fn fmt_symbols(&self, f: &mut std::fmt::Formatter<'_>, symbols: Symbols) -> std::fmt::Result {
+        let c = match symbols {
+            Symbols::Unicode => self.symbol(),
+            Symbols::Ascii => self.letter(),
+        };
+
+        f.pad(c.encode_utf8(&mut [0; 4]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test1() {
+        assert_eq!(Suit::iter().count(), 4);
+
+        for (elem, suit) in Suit::iter().enumerate() {
+            assert_eq!(suit.elem(), elem);
+            assert_eq!(Suit::from_elem(elem), Some(suit));
+            assert_eq!(Suit::from_char(SUITS[elem]), Some(suit));
+            assert_eq!(Suit::from_char(SUITS_ASCII[elem]), Some(suit));
+            assert_eq!(
+                Suit::from_char(SUITS_ASCII[elem].to_ascii_lowercase()),
+                Some(suit)
+            );
+            assert_eq!(format!("{suit}"), SUITS[elem].to_string());
+        }
+
+        assert_eq!(Suit::from_elem(4), None);
+        assert_eq!(Suit::from_char('X'), None);
+        assert!(Suit::Hearts < Suit::Spades);
+    }
+}
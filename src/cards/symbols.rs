@@ -0,0 +1,37 @@
+use std::fmt::{Display, Formatter, Result};
+
+/// How suits are written when cards are displayed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Symbols {
+    /// Suit symbols (♥ ♣ ♦ ♠)
+    #[default]
+    Unicode,
+    /// ASCII letters (H C D S)
+    Ascii,
+}
+
+/// Displays cards with a choice of suit symbols. `Display` always uses the Unicode symbols
+pub trait DisplaySymbols {
+    /// Formats the value with the suits written as given
+    fn fmt_symbols(&self, f: &mut Formatter<'_>, symbols: Symbols) -> Result;
+
+    /// Returns an adaptor which displays the value with the suits written as given
+    fn display(&self, symbols: Symbols) -> WithSymbols<'_, Self> {
+        WithSymbols {
+            value: self,
+            symbols,
+        }
+    }
+}
+
+/// Displays a value with a choice of suit symbols
+pub struct WithSymbols<'a, T: ?Sized> {
+    value: &'a T,
+    symbols: Symbols,
+}
+
+impl<T: DisplaySymbols + ?Sized> Display for WithSymbols<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        self.value.fmt_symbols(f, self.symbols)
+    }
+}
@@ -0,0 +1,277 @@
+use std::cmp::Ordering;
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use rayon::Scope;
+
+#[cfg(feature = "trace")]
+use crate::cards::BoardLayout;
+
+use super::{state::State, Progress, Results, Split, Stop, Strategy};
+
+/// Number of games a thread plays before adding its results to the progress
+const PROGRESS_GAMES: usize = 1 << 16;
+
+/// Plays all games from a state synchronously, depth first, with branches near the root
+/// of the game tree split between threads in the rayon thread pool
+pub fn play_dfs(state: State, strategy: Strategy, split: Split, stop: Stop) -> Results {
+    let progress = Progress::new(state.player_count() as u8);
+
+    play_dfs_watched(state, strategy, split, stop, progress)
+}
+
+/// Plays all games from a state like [play_dfs], adding results to the progress as the
+/// search goes so it can be watched from another thread. The progress should be new
+pub fn play_dfs_watched(
+    state: State,
+    strategy: Strategy,
+    split: Split,
+    stop: Stop,
+    progress: Progress,
+) -> Results {
+    let shared = Shared {
+        strategy,
+        split,
+        stop,
+        queued: AtomicUsize::new(0),
+        progress,
+    };
+
+    rayon::scope(|scope| search(scope, state, 1.0, &shared));
+
+    shared.progress.results()
+}
+
+/// Search parameters and state shared between threads
+struct Shared {
+    strategy: Strategy,
+    split: Split,
+    stop: Stop,
+    /// Number of branches split off which haven't been started by a thread yet
+    queued: AtomicUsize,
+    /// Results added by the searches so far
+    progress: Progress,
+}
+
+/// Searches the game tree below a state which makes up the given fraction of the whole game
+/// tree, adding the results to the progress as it goes
+fn search<'s>(scope: &Scope<'s>, state: State, weight: f64, shared: &'s Shared) {
+    let strategy = shared.strategy;
+
+    let mut results = Results::new(state.player_count() as u8);
+
+    // States at branch points still to be played with their fraction of the game tree
+    let mut stack = vec![(state, weight)];
+
+    while let Some((mut state, mut weight)) = stack.pop() {
+        if shared.stop.is_stopped() {
+            results.record_stopped();
+            break;
+        }
+
+        #[cfg(feature = "trace")]
+        println!("-- Start --");
+
+        'outer: loop {
+            'inner: {
+                #[cfg(feature = "trace")]
+                println!("Board:\n{}", BoardLayout::new(state.board()));
+
+                // Calculate playable cards
+                let (playable_cards, no_consequence_cards, sequence_cards) = state.playable_cards();
+
+                #[cfg(not(feature = "nostats"))]
+                let all_playable = playable_cards.clone();
+
+                let card_set = strategy.choose_cards(
+                    no_consequence_cards,
+                    sequence_cards,
+                    playable_cards,
+                    state.cur_player_cards(),
+                    #[cfg(not(feature = "nostats"))]
+                    state.cur_player(),
+                    #[cfg(not(feature = "nostats"))]
+                    &mut results,
+                );
+
+                // Make a move if possible
+                let card = match card_set.len().cmp(&1) {
+                    Ordering::Less => {
+                        // No cards to play
+                        #[cfg(not(feature = "nostats"))]
+                        state.add_miss();
+
+                        break 'inner;
+                    }
+                    Ordering::Equal => {
+                        // One card to play
+                        card_set.card_iterator().next().unwrap()
+                    }
+                    _ => {
+                        // Multiple choices
+                        let mut card_iter = card_set.card_iterator();
+
+                        let first_card = card_iter.next().unwrap();
+
+                        // Each choice gets an equal share of this part of the tree
+                        weight /= card_set.len() as f64;
+
+                        let split = !cfg!(feature = "trace")
+                            && shared.split.rayon(
+                                state.cards_played(),
+                                shared.queued.load(AtomicOrdering::Relaxed),
+                            );
+
+                        for c in card_iter {
+                            let mut next_state = state.clone();
+
+                            #[cfg(not(feature = "nostats"))]
+                            next_state.add_held(&all_playable, &c);
+
+                            #[cfg(feature = "trace")]
+                            println!(
+                                "Player {} playing {} with backtrack",
+                                state.cur_player() + 1,
+                                c
+                            );
+
+                            // Play the card
+                            next_state.play_card(c);
+
+                            // Move to next player
+                            next_state.next_player();
+
+                            if split {
+                                // Let another thread take it
+                                shared.queued.fetch_add(1, AtomicOrdering::Relaxed);
+
+                                scope.spawn(move |scope| {
+                                    shared.queued.fetch_sub(1, AtomicOrdering::Relaxed);
+                                    search(scope, next_state, weight, shared)
+                                });
+                            } else {
+                                // Play it after this line
+                                stack.push((next_state, weight));
+                            }
+                        }
+
+                        first_card
+                    }
+                };
+
+                #[cfg(feature = "trace")]
+                println!("Player {} playing {}", state.cur_player() + 1, card);
+
+                #[cfg(not(feature = "nostats"))]
+                state.add_held(&all_playable, &card);
+
+                // Play the card
+                state.play_card(card);
+
+                if state.cur_player_cards().is_empty() {
+                    // Player has won
+                    results.record_win(&state);
+                    results.record_covered(weight);
+
+                    if results.games() >= PROGRESS_GAMES {
+                        let players = state.player_count() as u8;
+                        shared
+                            .progress
+                            .add(mem::replace(&mut results, Results::new(players)));
+                    }
+
+                    #[cfg(feature = "trace")]
+                    println!("Win for player {}", state.cur_player() + 1);
+
+                    break 'outer;
+                }
+            }
+
+            // Move to next player
+            state.next_player();
+        }
+
+        #[cfg(feature = "trace")]
+        println!("-- End --");
+    }
+
+    shared.progress.add(results);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{play, tests::endgame_state};
+
+    #[test]
+    fn test_complete() {
+        let results = play_dfs(
+            endgame_state(),
+            Strategy::Dumb,
+            Split::default(),
+            Stop::new(),
+        );
+
+        assert!(results.is_complete());
+        assert!(results.games() > 0);
+        assert!((results.coverage() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stopped() {
+        let stop = Stop::new();
+        stop.stop();
+
+        let results = play_dfs(endgame_state(), Strategy::Dumb, Split::default(), stop);
+
+        assert!(!results.is_complete());
+        assert_eq!(results.games(), 0);
+        assert_eq!(results.coverage(), 0.0);
+    }
+
+    #[test]
+    fn test_progress() {
+        let progress = Progress::new(4);
+
+        let results = play_dfs_watched(
+            endgame_state(),
+            Strategy::Dumb,
+            Split::default(),
+            Stop::new(),
+            progress.clone(),
+        );
+
+        assert_eq!(progress.results().games(), results.games());
+        assert_eq!(
+            results.games(),
+            play_dfs(
+                endgame_state(),
+                Strategy::Dumb,
+                Split::default(),
+                Stop::new()
+            )
+            .games()
+        );
+    }
+
+    #[test]
+    fn test_same_as_tokio() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        for strategy in [Strategy::NoConsequence, Strategy::Preferred, Strategy::Dumb] {
+            let dfs = play_dfs(endgame_state(), strategy, Split::default(), Stop::new());
+            let tokio = runtime.block_on(play(
+                endgame_state(),
+                strategy,
+                Split::default(),
+                Stop::new(),
+            ));
+
+            assert_eq!(dfs.games(), tokio.games());
+
+            for (d, t) in dfs.player_results().iter().zip(tokio.player_results()) {
+                assert_eq!(d.wins(), t.wins());
+            }
+        }
+    }
+}
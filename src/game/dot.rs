@@ -0,0 +1,336 @@
+use std::fmt::Write;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+#[cfg(not(feature = "nostats"))]
+use super::Results;
+use super::{play_dfs, sample_wins_with, state::State, Observation, Split, Stop, Strategy};
+
+/// Fill colours for leaf nodes by winning player
+const PLAYER_COLOURS: [&str; 8] = [
+    "lightcoral",
+    "lightblue",
+    "palegreen",
+    "gold",
+    "plum",
+    "orange",
+    "cyan",
+    "pink",
+];
+
+/// Limits on the size of an exported game tree
+#[derive(Debug, Clone)]
+pub struct DotLimits {
+    /// Maximum number of moves from the starting position
+    pub depth: usize,
+    /// Maximum number of nodes to draw, including won games and pruned sub-trees. The
+    /// starting position is always drawn
+    pub nodes: usize,
+    /// Number of random games played to estimate the wins in each pruned sub-tree, or
+    /// `None` to play all of its games and count the wins exactly
+    pub probes: Option<usize>,
+}
+
+/// Exports the game tree from a position as a Graphviz DOT graph. Sub-trees beyond the limits
+/// are drawn as a single node with the number of games each player wins, or with the share
+/// of the games each player is estimated to win from random games played with the seed
+pub fn export_dot(state: State, strategy: Strategy, limits: &DotLimits, seed: u64) -> String {
+    let mut writer = DotWriter {
+        strategy,
+        limits: limits.clone(),
+        rng: StdRng::seed_from_u64(seed),
+        nodes: 0,
+        reserved: 0,
+        #[cfg(not(feature = "nostats"))]
+        results: Results::new(state.player_count() as u8),
+        out: String::new(),
+    };
+
+    writeln!(writer.out, "digraph sevens {{").unwrap();
+    writeln!(writer.out, "  node [fontname=\"sans-serif\"];").unwrap();
+    writeln!(writer.out, "  edge [fontname=\"sans-serif\"];").unwrap();
+
+    writer.node(state, 0);
+
+    writeln!(writer.out, "}}").unwrap();
+
+    writer.out
+}
+
+struct DotWriter {
+    strategy: Strategy,
+    limits: DotLimits,
+    rng: StdRng,
+    nodes: usize,
+    /// Number of nodes still to be drawn for children of expanded nodes
+    reserved: usize,
+    #[cfg(not(feature = "nostats"))]
+    results: Results,
+    out: String,
+}
+
+impl DotWriter {
+    /// Writes the node for a position and its sub-tree, returning the node ID
+    fn node(&mut self, state: State, depth: usize) -> usize {
+        let id = self.next_id();
+
+        if depth >= self.limits.depth {
+            return self.pruned(id, state);
+        }
+
+        let card_set = self.strategy.choose_cards_for(
+            &Observation::new(&state, &[]),
+            #[cfg(not(feature = "nostats"))]
+            &mut self.results,
+        );
+
+        // Each child needs a node, so only expand if there is room for them all
+        let children = card_set.len().max(1);
+
+        if self.nodes + self.reserved + children > self.limits.nodes {
+            return self.pruned(id, state);
+        }
+
+        self.reserved += children;
+
+        let hand_sizes = state
+            .all_player_cards()
+            .iter()
+            .map(|c| format!("{}", c.len()))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        writeln!(
+            self.out,
+            "  n{id} [label=\"P{}\\n{hand_sizes}\"];",
+            state.cur_player() + 1
+        )
+        .unwrap();
+
+        if card_set.is_empty() {
+            // Player misses a go
+            let mut next_state = state;
+            next_state.next_player();
+
+            self.reserved -= 1;
+            let child = self.node(next_state, depth + 1);
+            self.edge(id, child, "pass");
+        } else {
+            for card in card_set.card_iterator() {
+                let mut next_state = state.clone();
+                let label = format!("{card}");
+
+                next_state.play_card(card);
+
+                self.reserved -= 1;
+                let child = if next_state.cur_player_cards().is_empty() {
+                    self.win(next_state.cur_player())
+                } else {
+                    next_state.next_player();
+                    self.node(next_state, depth + 1)
+                };
+
+                self.edge(id, child, &label);
+            }
+        }
+
+        id
+    }
+
+    /// Writes a node summarising the wins in a sub-tree which isn't drawn
+    fn pruned(&mut self, id: usize, state: State) -> usize {
+        let (title, label) = match self.limits.probes {
+            None => {
+                let results = play_dfs(state, self.strategy, Split::default(), Stop::new());
+
+                let label = results
+                    .player_results()
+                    .iter()
+                    .enumerate()
+                    .map(|(player, r)| format!("P{}: {}", player + 1, r.wins()))
+                    .collect::<Vec<_>>()
+                    .join("\\n");
+
+                ("pruned", label)
+            }
+            Some(probes) => {
+                // Random games are much quicker than playing every game of a large sub-tree
+                let wins = sample_wins_with(state, self.strategy, probes, &mut self.rng);
+
+                let label = wins
+                    .iter()
+                    .enumerate()
+                    .map(|(player, wins)| format!("P{}: {:.1}%", player + 1, wins * 100.0))
+                    .collect::<Vec<_>>()
+                    .join("\\n");
+
+                ("pruned (est.)", label)
+            }
+        };
+
+        writeln!(
+            self.out,
+            "  n{id} [shape=box, style=dashed, label=\"{title}\\n{label}\"];"
+        )
+        .unwrap();
+
+        id
+    }
+
+    /// Writes a leaf node for a won game
+    fn win(&mut self, player: usize) -> usize {
+        let id = self.next_id();
+
+        writeln!(
+            self.out,
+            "  n{id} [shape=doublecircle, style=filled, fillcolor={}, label=\"P{} wins\"];",
+            PLAYER_COLOURS[player % PLAYER_COLOURS.len()],
+            player + 1
+        )
+        .unwrap();
+
+        id
+    }
+
+    fn edge(&mut self, from: usize, to: usize, label: &str) {
+        writeln!(self.out, "  n{from} -> n{to} [label=\"{label}\"];").unwrap();
+    }
+
+    fn next_id(&mut self) -> usize {
+        let id = self.nodes;
+        self.nodes += 1;
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{play_dfs, tests::endgame_state, Split, Stop};
+
+    fn count(dot: &str, pattern: &str) -> usize {
+        dot.lines().filter(|l| l.contains(pattern)).count()
+    }
+
+    fn count_nodes(dot: &str) -> usize {
+        dot.lines()
+            .filter(|l| l.starts_with("  n") && !l.starts_with("  node") && !l.contains(" -> "))
+            .count()
+    }
+
+    #[test]
+    fn test_full_tree() {
+        let limits = DotLimits {
+            depth: 100,
+            nodes: 100_000,
+            probes: None,
+        };
+
+        let dot = export_dot(endgame_state(), Strategy::NoConsequence, &limits, 1);
+        let results = play_dfs(
+            endgame_state(),
+            Strategy::NoConsequence,
+            Split::default(),
+            Stop::new(),
+        );
+
+        assert!(dot.starts_with("digraph sevens {"));
+        assert!(dot.trim_end().ends_with('}'));
+
+        // A tree with a leaf for every game and nothing pruned
+        assert_eq!(count(&dot, " -> "), count_nodes(&dot) - 1);
+        assert_eq!(count(&dot, "wins\""), results.games());
+        assert_eq!(count(&dot, "pruned"), 0);
+
+        // Leaves are coloured by the winner
+        for (player, player_results) in results.player_results().iter().enumerate() {
+            assert_eq!(
+                count(&dot, &format!("fillcolor={}", PLAYER_COLOURS[player])),
+                player_results.wins()
+            );
+        }
+    }
+
+    /// Returns the numbers in the labels of the pruned nodes, one list per node
+    fn pruned_labels(dot: &str) -> Vec<Vec<f64>> {
+        dot.lines()
+            .filter(|l| l.contains("pruned"))
+            .map(|l| {
+                l.split("\\n")
+                    .filter_map(|s| s.split_once(": "))
+                    .map(|(_, n)| n.trim_end_matches(['%', '"', ']', ';']))
+                    .map(|n| n.parse::<f64>().unwrap())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pruned_tree() {
+        let limits = DotLimits {
+            depth: 2,
+            nodes: 100_000,
+            probes: None,
+        };
+
+        let dot = export_dot(endgame_state(), Strategy::Dumb, &limits, 1);
+
+        // Player 2 can only play the J♣, then player 3 has two choices. Both are cut off
+        // as nobody can win by then
+        assert_eq!(count_nodes(&dot), 4);
+        assert_eq!(count(&dot, " -> "), 3);
+        assert_eq!(count(&dot, "pruned\\n"), 2);
+        assert_eq!(count(&dot, "wins\""), 0);
+
+        // The pruned sub-trees hold every game
+        let results = play_dfs(
+            endgame_state(),
+            Strategy::Dumb,
+            Split::default(),
+            Stop::new(),
+        );
+        let labels = pruned_labels(&dot);
+
+        for (player, player_results) in results.player_results().iter().enumerate() {
+            let wins = labels.iter().map(|l| l[player]).sum::<f64>();
+            assert_eq!(wins as usize, player_results.wins());
+        }
+    }
+
+    #[test]
+    fn test_estimated_tree() {
+        let limits = DotLimits {
+            depth: 2,
+            nodes: 100_000,
+            probes: Some(100),
+        };
+
+        let dot = export_dot(endgame_state(), Strategy::Dumb, &limits, 1);
+
+        assert_eq!(count(&dot, "pruned (est.)"), 2);
+
+        // Estimated shares of the wins add up to 100%
+        for label in pruned_labels(&dot) {
+            assert!((label.iter().sum::<f64>() - 100.0).abs() < 0.5);
+        }
+
+        // The same seed gives the same estimates
+        assert_eq!(export_dot(endgame_state(), Strategy::Dumb, &limits, 1), dot);
+    }
+
+    #[test]
+    fn test_node_limit() {
+        for nodes in 1..50 {
+            let limits = DotLimits {
+                depth: 100,
+                nodes,
+                probes: Some(1),
+            };
+
+            let dot = export_dot(endgame_state(), Strategy::Dumb, &limits, 1);
+
+            assert!(count_nodes(&dot) <= nodes);
+            assert_eq!(count(&dot, " -> "), count_nodes(&dot) - 1);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::time::{Duration, Instant};
+
+use numformat::NumFormat;
+use rand::Rng;
+
+#[cfg(not(feature = "nostats"))]
+use super::Results;
+use super::{play_dfs, state::State, Observation, Split, Stop, Strategy};
+
+/// Estimated size of a game tree
+#[derive(Debug, Clone)]
+pub struct Estimate {
+    /// Number of random probes made
+    pub probes: usize,
+    /// Estimated number of games
+    pub games: f64,
+    /// Standard error of the estimated number of games
+    pub games_error: f64,
+    /// Estimated number of moves in the game tree
+    pub nodes: f64,
+}
+
+impl Estimate {
+    /// Prints the estimated games and moves, and the time to play the games at a rate
+    /// measured with [`games_per_second`]
+    pub fn print(&self, games_per_second: f64) {
+        println!(
+            "Estimated games: {} (± {:.1}%)",
+            (self.games.round() as usize).num_format(),
+            (self.games_error / self.games) * 100.0
+        );
+        println!(
+            "Estimated moves: {}",
+            (self.nodes.round() as usize).num_format()
+        );
+
+        println!(
+            "Games per second: {}",
+            (games_per_second.round() as usize).num_format()
+        );
+
+        if games_per_second > 0.0 {
+            println!(
+                "Estimated time: {}",
+                format_seconds(self.games / games_per_second)
+            );
+        } else {
+            // No games finished in the timed run
+            println!("Estimated time: unknown");
+        }
+    }
+}
+
+/// Estimates the size of the game tree from a state using Knuth's estimator. Each probe
+/// plays a single game choosing randomly at each branch point, and the product of the
+/// number of choices at each branch point estimates the number of games
+pub fn estimate(state: State, strategy: Strategy, probes: usize) -> Estimate {
+    let mut rng = rand::thread_rng();
+
+    #[cfg(not(feature = "nostats"))]
+    let mut results = Results::new(state.player_count() as u8);
+
+    let mut games_sum = 0.0;
+    let mut games_sum_sq = 0.0;
+    let mut nodes_sum = 0.0;
+
+    for _ in 0..probes {
+        let probe = probe(
+            state.clone(),
+            strategy,
+            &mut rng,
+            #[cfg(not(feature = "nostats"))]
+            &mut results,
+        );
+
+        games_sum += probe.games;
+        games_sum_sq += probe.games * probe.games;
+        nodes_sum += probe.nodes;
+    }
+
+    let n = probes.max(1) as f64;
+    let games = games_sum / n;
+    let variance = (games_sum_sq / n - games * games).max(0.0);
+
+    Estimate {
+        probes,
+        games,
+        games_error: (variance / n).sqrt(),
+        nodes: nodes_sum / n,
+    }
+}
+
+/// Estimates the fraction of games from a state won by each player by weighting the
+/// winner of each random game by the number of games it stands for
+pub fn sample_wins(state: State, strategy: Strategy, probes: usize) -> Vec<f64> {
+    sample_wins_with(state, strategy, probes, &mut rand::thread_rng())
+}
+
+/// Estimates the fraction of games from a state won by each player, choosing randomly with
+/// the given random number generator
+pub fn sample_wins_with(
+    state: State,
+    strategy: Strategy,
+    probes: usize,
+    rng: &mut impl Rng,
+) -> Vec<f64> {
+    #[cfg(not(feature = "nostats"))]
+    let mut results = Results::new(state.player_count() as u8);
+
+    let mut wins = vec![0.0; state.player_count()];
+
+    for _ in 0..probes {
+        let probe = probe(
+            state.clone(),
+            strategy,
+            rng,
+            #[cfg(not(feature = "nostats"))]
+            &mut results,
+        );
+
+        wins[probe.winner] += probe.games;
+    }
+
+    let total = wins.iter().sum::<f64>();
+
+    if total > 0.0 {
+        wins.iter_mut().for_each(|w| *w /= total);
+    }
+
+    wins
+}
+
+/// Outcome of playing a single random game
+struct Probe {
+    /// Number of games the game stands for
+    games: f64,
+    /// Number of moves the game stands for
+    nodes: f64,
+    /// Winning player
+    winner: usize,
+}
+
+/// Plays a single game choosing randomly between the strategy's cards at each branch point
+fn probe(
+    mut state: State,
+    strategy: Strategy,
+    rng: &mut impl Rng,
+    #[cfg(not(feature = "nostats"))] results: &mut Results,
+) -> Probe {
+    // Number of games the current position stands for
+    let mut weight = 1.0;
+    let mut nodes = 0.0;
+
+    loop {
+        let card_set = strategy.choose_cards_for(
+            &Observation::new(&state, &[]),
+            #[cfg(not(feature = "nostats"))]
+            results,
+        );
+
+        nodes += weight;
+
+        if !card_set.is_empty() {
+            let choices = card_set.len();
+            weight *= choices as f64;
+
+            let card = card_set
+                .card_iterator()
+                .nth(rng.gen_range(0..choices))
+                .unwrap();
+
+            state.play_card(card);
+
+            if state.cur_player_cards().is_empty() {
+                break;
+            }
+        }
+
+        state.next_player();
+    }
+
+    Probe {
+        games: weight,
+        nodes,
+        winner: state.cur_player(),
+    }
+}
+
+/// Measures the rate games are played from a state by playing for a short time
+pub fn games_per_second(state: State, strategy: Strategy, split: Split, time: Duration) -> f64 {
+    let stop = Stop::after(time);
+
+    let start = Instant::now();
+    let results = play_dfs(state, strategy, split, stop);
+    let elapsed = start.elapsed();
+
+    results.games() as f64 / elapsed.as_secs_f64()
+}
+
+/// Formats a number of seconds as days, hours, minutes and seconds
+fn format_seconds(seconds: f64) -> String {
+    let secs = seconds.round() as u64;
+
+    if secs < 60 {
+        return format!("{seconds:.1}s");
+    }
+
+    let (days, hours, mins, secs) = (
+        secs / 86_400,
+        (secs / 3_600) % 24,
+        (secs / 60) % 60,
+        secs % 60,
+    );
+
+    if days > 0 {
+        format!("{days}d {hours}h {mins}m")
+    } else if hours > 0 {
+        format!("{hours}h {mins}m {secs}s")
+    } else {
+        format!("{mins}m {secs}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::tests::endgame_state;
+
+    #[test]
+    fn test_estimate() {
+        let state = endgame_state();
+
+        let games = play_dfs(state.clone(), Strategy::Dumb, Split::default(), Stop::new()).games();
+        let estimate = estimate(state, Strategy::Dumb, 5_000);
+
+        assert_eq!(estimate.probes, 5_000);
+        assert!(estimate.nodes > estimate.games);
+
+        // Within 5 standard errors of the actual number of games
+        assert!((estimate.games - games as f64).abs() < 5.0 * estimate.games_error.max(1.0));
+    }
+
+    #[test]
+    fn test_format_seconds() {
+        assert_eq!(format_seconds(12.34), "12.3s");
+        assert_eq!(format_seconds(125.0), "2m 5s");
+        assert_eq!(format_seconds(3_725.0), "1h 2m 5s");
+        assert_eq!(format_seconds(2.0 * 86_400.0 + 3_725.0), "2d 1h 2m");
+    }
+}
@@ -0,0 +1,207 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::cards::CardCollection;
+
+use super::{sample_wins_with, state::State, Strategy, ALL_CARDS};
+
+/// Work done evaluating a hand
+#[derive(Debug, Clone)]
+pub struct EvaluateLimits {
+    /// Number of deals of the other cards to play
+    pub deals: usize,
+    /// Number of random games to play for each deal
+    pub probes: usize,
+}
+
+/// How often a hand wins when the other cards are dealt at random
+#[derive(Debug, Clone)]
+pub struct HandEvaluation {
+    /// Number of deals played
+    pub deals: usize,
+    /// Average fraction of games won over the deals
+    pub win_rate: f64,
+    /// Standard error of the average
+    pub win_rate_error: f64,
+    /// Win rate of an average hand, given before any card has been played when every hand
+    /// is equally likely to win
+    pub average: Option<f64>,
+    /// Fraction of games won in the deal itself
+    pub this_deal: f64,
+}
+
+impl std::fmt::Display for HandEvaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Win rate: {:.1}% (± {:.1}%)",
+            self.win_rate * 100.0,
+            self.win_rate_error * 100.0
+        )?;
+
+        if let Some(average) = self.average {
+            write!(f, ", an average hand wins {:.1}%", average * 100.0)?;
+        }
+
+        write!(f, "\nWin rate in this deal: {:.1}%", self.this_deal * 100.0)
+    }
+}
+
+/// Evaluates a player's hand in general rather than for a single deal. The cards the player
+/// can't see are dealt at random to the other players many times, keeping the number of
+/// cards each holds, and the fraction of games the player wins with the strategy is
+/// averaged over the deals. Each deal is dealt and played with its own seed, the next one
+/// after the seed of the deal before, so the same seed gives the same evaluation. The deal
+/// itself is played with ten times as many random games for comparison
+pub fn evaluate_hand(
+    state: &State,
+    player: usize,
+    strategy: Strategy,
+    limits: &EvaluateLimits,
+    seed: u64,
+) -> HandEvaluation {
+    let rates = (0..limits.deals)
+        .into_par_iter()
+        .map(|deal| {
+            let mut rng = StdRng::seed_from_u64(seed.wrapping_add(deal as u64));
+            let state = redeal(state, player, &mut rng);
+
+            sample_wins_with(state, strategy, limits.probes, &mut rng)[player]
+        })
+        .collect::<Vec<_>>();
+
+    let n = limits.deals.max(1) as f64;
+    let win_rate = rates.iter().sum::<f64>() / n;
+    let variance = rates.iter().map(|r| (r - win_rate).powi(2)).sum::<f64>() / n;
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let this_deal = sample_wins_with(state.clone(), strategy, limits.probes * 10, &mut rng)[player];
+
+    HandEvaluation {
+        deals: limits.deals,
+        win_rate,
+        win_rate_error: (variance / n).sqrt(),
+        average: state
+            .board()
+            .is_empty()
+            .then(|| 1.0 / state.player_count() as f64),
+        this_deal,
+    }
+}
+
+/// Deals the cards not held by a player or on the board to the other players at random,
+/// each getting the same number of cards they hold now
+fn redeal(state: &State, player: usize, rng: &mut impl Rng) -> State {
+    let hands = state.all_player_cards();
+
+    let unseen =
+        CardCollection::new_from_raw(ALL_CARDS & !state.board().raw() & !hands[player].raw());
+
+    let mut cards = unseen.card_iterator().collect::<Vec<_>>();
+    cards.shuffle(rng);
+
+    let mut cards = cards.into_iter();
+
+    let hands = hands
+        .iter()
+        .enumerate()
+        .map(|(p, hand)| {
+            if p == player {
+                hand.clone()
+            } else {
+                cards
+                    .by_ref()
+                    .take(hand.len())
+                    .fold(CardCollection::new(), |mut acc, c| {
+                        acc.add(c);
+                        acc
+                    })
+            }
+        })
+        .collect();
+
+    state.with_hands(hands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, Deck, Rank, Suit};
+    use crate::game::tests::endgame_state;
+
+    #[test]
+    fn test_redeal() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let mut deck = Deck::new();
+        deck.shuffle_with(&mut rng);
+
+        let state = State::new(4, deck);
+        let seven_hearts = Card::new_from_suit_rank(Suit::Hearts, Rank::Seven);
+
+        for _ in 0..100 {
+            let redealt = redeal(&state, 2, &mut rng);
+
+            assert_eq!(redealt.all_player_cards()[2], state.all_player_cards()[2]);
+
+            for (a, b) in redealt
+                .all_player_cards()
+                .iter()
+                .zip(state.all_player_cards())
+            {
+                assert_eq!(a.len(), b.len());
+            }
+
+            // Every card is dealt once
+            let all = redealt
+                .all_player_cards()
+                .iter()
+                .fold(0, |acc, h| acc | h.raw());
+            assert_eq!(all, ALL_CARDS);
+
+            // Player with the 7♥ starts
+            assert!(redealt.cur_player_cards().contains(seven_hearts.clone()));
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let state = endgame_state();
+
+        let limits = EvaluateLimits {
+            deals: 200,
+            probes: 50,
+        };
+
+        let evaluations = (0..4)
+            .map(|p| evaluate_hand(&state, p, Strategy::Preferred, &limits, 3))
+            .collect::<Vec<_>>();
+
+        for e in &evaluations {
+            assert_eq!(e.deals, 200);
+            assert!((0.0..=1.0).contains(&e.win_rate));
+            assert!(e.win_rate_error < 0.1);
+            assert!((0.0..=1.0).contains(&e.this_deal));
+
+            // Cards have been played so there's no average hand to compare with
+            assert_eq!(e.average, None);
+            assert!(!e.to_string().contains("average"));
+        }
+
+        // The same seed gives the same evaluation
+        let again = evaluate_hand(&state, 3, Strategy::Preferred, &limits, 3);
+        assert_eq!(again.win_rate, evaluations[3].win_rate);
+
+        // Before any card is played an average hand wins one game in four
+        let start = State::new(4, Deck::new());
+        let evaluation = evaluate_hand(&start, 0, Strategy::Preferred, &limits, 3);
+        assert_eq!(evaluation.average, Some(0.25));
+        assert!(evaluation
+            .to_string()
+            .contains("an average hand wins 25.0%"));
+
+        // Two cards left beats hands waiting on cards others hold
+        assert!(evaluations[3].win_rate > evaluations[1].win_rate + 0.1);
+        assert!(evaluations[3].win_rate > evaluations[2].win_rate + 0.1);
+    }
+}
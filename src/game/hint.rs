@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use crate::cards::Card;
+
+use super::{play_dfs, sample_wins, state::State, Split, Stop, Strategy};
+
+/// Limits on the work done to find the win rate for each card
+#[derive(Debug, Clone)]
+pub struct HintLimits {
+    /// Time allowed to play every game before falling back to random games
+    pub time: Duration,
+    /// Number of random games to play if every game can't be played in time
+    pub probes: usize,
+}
+
+/// Win rate for the current player from playing a card
+#[derive(Debug, Clone)]
+pub struct CardHint {
+    /// Card to play
+    pub card: Card,
+    /// Fraction of the games that follow which are won by the player
+    pub win_rate: f64,
+    /// Number of games if every game was played, or None if estimated from random games
+    pub games: Option<usize>,
+}
+
+/// Finds the win rate for the current player from each card they can play, assuming all
+/// players go on to play with the strategy. Best cards first
+pub fn hint(state: &State, strategy: Strategy, limits: &HintLimits) -> Vec<CardHint> {
+    let player = state.cur_player();
+
+    let mut hints = state
+        .playable_cards()
+        .0
+        .card_iterator()
+        .map(|card| {
+            let mut next_state = state.clone();
+            next_state.play_card(card.clone());
+
+            if next_state.cur_player_cards().is_empty() {
+                // Winning card
+                return CardHint {
+                    card,
+                    win_rate: 1.0,
+                    games: Some(1),
+                };
+            }
+
+            next_state.next_player();
+
+            // Try playing every game
+            let results = play_dfs(
+                next_state.clone(),
+                strategy,
+                Split::default(),
+                Stop::after(limits.time),
+            );
+
+            if results.is_complete() {
+                CardHint {
+                    card,
+                    win_rate: results.player_results()[player].wins() as f64
+                        / results.games() as f64,
+                    games: Some(results.games()),
+                }
+            } else {
+                // Too many games so estimate from random games
+                CardHint {
+                    card,
+                    win_rate: sample_wins(next_state, strategy, limits.probes)[player],
+                    games: None,
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    hints.sort_by(|a, b| b.win_rate.total_cmp(&a.win_rate));
+
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::tests::endgame_state;
+
+    #[test]
+    fn test_hint() {
+        let state = endgame_state();
+
+        let limits = HintLimits {
+            time: Duration::from_secs(60),
+            probes: 1000,
+        };
+
+        let hints = hint(&state, Strategy::Dumb, &limits);
+
+        // One hint for each playable card, best first
+        assert_eq!(hints.len(), state.playable_cards().0.len());
+        assert!(hints.windows(2).all(|w| w[0].win_rate >= w[1].win_rate));
+        assert!(hints.iter().all(|h| h.games.is_some()));
+
+        // Random games give similar win rates
+        let limits = HintLimits {
+            time: Duration::ZERO,
+            probes: 20_000,
+        };
+
+        let estimates = hint(&state, Strategy::Dumb, &limits);
+
+        for h in &hints {
+            let e = estimates.iter().find(|e| e.card == h.card).unwrap();
+            assert!((e.win_rate - h.win_rate).abs() < 0.05);
+        }
+    }
+}
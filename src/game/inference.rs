@@ -0,0 +1,277 @@
+use crate::cards::CardCollection;
+
+use super::{Move, Observation, State, ALL_CARDS};
+
+/// The cards each player could still hold as far as one player (the observer) knows. Cards
+/// played are removed from everyone, and a player who passes can't hold any of the cards
+/// which could have been played at the time
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inference {
+    observer: usize,
+    /// Cards each player could hold
+    possible: Vec<CardCollection>,
+}
+
+impl Inference {
+    /// Starts from what the observer can see: their own hand and the board. The other
+    /// players could hold any of the remaining cards
+    pub fn new(
+        observer: usize,
+        hand: &CardCollection,
+        board: &CardCollection,
+        player_count: usize,
+    ) -> Self {
+        let unseen = CardCollection::new_from_raw(ALL_CARDS & !hand.raw() & !board.raw());
+
+        let possible = (0..player_count)
+            .map(|player| {
+                if player == observer {
+                    hand.clone()
+                } else {
+                    unseen.clone()
+                }
+            })
+            .collect();
+
+        Self { observer, possible }
+    }
+
+    /// Works out what the player to move can infer from the moves they have seen
+    pub fn from_observation(view: &Observation) -> Self {
+        let history = view.history();
+        let observer = view.player();
+
+        // Undo the moves to find the board and the observer's hand before them
+        let mut board = view.board().clone();
+        let mut hand = view.hand().clone();
+
+        for m in history {
+            if let Move::Play(player, card) = m {
+                board.remove(card.clone());
+
+                if *player == observer {
+                    hand.add(card.clone());
+                }
+            }
+        }
+
+        let mut inference = Self::new(observer, &hand, &board, view.player_count());
+
+        for m in history {
+            inference.observe(m, &board);
+
+            if let Move::Play(_, card) = m {
+                board.add(card.clone());
+            }
+        }
+
+        inference.apply_hand_sizes(&view.hand_sizes());
+
+        inference
+    }
+
+    /// Updates for a move made when the board was as given
+    pub fn observe(&mut self, m: &Move, board: &CardCollection) {
+        match m {
+            Move::Play(_, card) => {
+                // Nobody holds a card once it's played
+                self.possible
+                    .iter_mut()
+                    .for_each(|p| p.remove(card.clone()));
+            }
+            Move::Pass(player) => {
+                // Player holds none of the cards they could have played
+                let open = State::open_cards(board);
+                let possible = &mut self.possible[*player];
+
+                *possible = CardCollection::new_from_raw(possible.raw() & !open.raw());
+            }
+        }
+    }
+
+    /// Narrows down the cards using the number of cards each player holds. A player who
+    /// could only hold as many cards as they have must hold all of them, so nobody else can
+    pub fn apply_hand_sizes(&mut self, hand_sizes: &[usize]) {
+        loop {
+            let mut changed = false;
+
+            for (player, size) in hand_sizes.iter().enumerate() {
+                if self.possible[player].len() != *size {
+                    continue;
+                }
+
+                let held = self.possible[player].raw();
+
+                for (other, possible) in self.possible.iter_mut().enumerate() {
+                    if other != player && possible.raw() & held != 0 {
+                        *possible = CardCollection::new_from_raw(possible.raw() & !held);
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    /// Returns the observing player
+    pub fn observer(&self) -> usize {
+        self.observer
+    }
+
+    /// Returns the cards a player could hold
+    pub fn possible(&self, player: usize) -> &CardCollection {
+        &self.possible[player]
+    }
+
+    /// Returns the cards a player must hold because nobody else could
+    pub fn known(&self, player: usize) -> CardCollection {
+        let others = self
+            .possible
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != player)
+            .fold(0, |acc, (_, p)| acc | p.raw());
+
+        CardCollection::new_from_raw(self.possible[player].raw() & !others)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, Deck};
+    use crate::game::State;
+
+    fn cards(s: &str) -> CardCollection {
+        s.parse().unwrap()
+    }
+
+    fn card(s: &str) -> Card {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        let hand = cards("7♥ 8♥ A♠");
+        let inference = Inference::new(1, &hand, &CardCollection::new(), 3);
+
+        assert_eq!(inference.observer(), 1);
+        assert_eq!(inference.possible(1), &hand);
+        assert_eq!(inference.possible(0).len(), 49);
+        assert!(!inference.possible(0).contains(card("7♥")));
+        assert_eq!(inference.possible(0), inference.possible(2));
+        assert_eq!(inference.known(1), hand);
+        assert!(inference.known(0).is_empty());
+    }
+
+    #[test]
+    fn test_pass() {
+        let mut inference = Inference::new(0, &cards("A♥ K♠"), &CardCollection::new(), 4);
+        let mut board = CardCollection::new();
+
+        inference.observe(&Move::Play(0, card("7♥")), &board);
+        board.add(card("7♥"));
+
+        // Player 2 can't hold any card which could be played next to the 7♥
+        inference.observe(&Move::Pass(1), &board);
+
+        for c in ["6♥", "8♥", "7♣", "7♦", "7♠"] {
+            assert!(!inference.possible(1).contains(card(c)));
+            assert!(inference.possible(2).contains(card(c)));
+        }
+
+        assert_eq!(inference.possible(1).len(), 52 - 2 - 1 - 5);
+
+        // Nobody holds a card once played
+        inference.observe(&Move::Play(2, card("8♥")), &board);
+        board.add(card("8♥"));
+
+        assert!((0..4).all(|p| !inference.possible(p).contains(card("8♥"))));
+
+        // Player 4 passes on a board with 6♥ and 9♥ open
+        inference.observe(&Move::Pass(3), &board);
+
+        assert!(!inference.possible(3).contains(card("9♥")));
+        assert!(!inference.possible(3).contains(card("6♥")));
+        assert!(inference.possible(1).contains(card("9♥")));
+    }
+
+    #[test]
+    fn test_hand_sizes() {
+        // Endgame with three players and two cards each
+        let board = CardCollection::new_from_raw(ALL_CARDS & !cards("A♥ 2♥ Q♠ K♠ Q♦ K♦").raw());
+        let mut inference = Inference::new(0, &cards("A♥ 2♥"), &board, 3);
+
+        // Player 2 passes while the Q♠ and Q♦ are open, so can't hold them
+        inference.observe(&Move::Pass(1), &board);
+
+        assert_eq!(inference.possible(1), &cards("K♠ K♦"));
+        assert!(inference.known(1).is_empty());
+
+        // So player 2 must hold both kings, leaving the queens for player 3
+        inference.apply_hand_sizes(&[2, 2, 2]);
+
+        assert_eq!(inference.known(1), cards("K♠ K♦"));
+        assert_eq!(inference.possible(2), &cards("Q♠ Q♦"));
+        assert_eq!(inference.known(2), cards("Q♠ Q♦"));
+    }
+
+    #[test]
+    fn test_from_observation() {
+        let mut state = State::new(3, Deck::new());
+        let start = state.clone();
+        let mut history = Vec::new();
+
+        // Play until someone passes
+        while history.iter().all(|m| !matches!(m, Move::Pass(_))) {
+            let (playable, _, _) = state.playable_cards();
+
+            match playable.card_iterator().next() {
+                Some(card) => {
+                    history.push(Move::Play(state.cur_player(), card.clone()));
+                    state.play_card(card);
+
+                    assert!(!state.cur_player_cards().is_empty());
+                }
+                None => history.push(Move::Pass(state.cur_player())),
+            }
+
+            state.next_player();
+        }
+
+        let view = Observation::new(&state, &history);
+        let inference = view.inference();
+
+        // Same as following the game move by move
+        let observer = state.cur_player();
+        let mut manual = Inference::new(
+            observer,
+            &start.all_player_cards()[observer],
+            &CardCollection::new(),
+            3,
+        );
+        let mut replay = start.clone();
+
+        for m in &history {
+            manual.observe(m, replay.board());
+
+            if let Move::Play(_, card) = m {
+                replay.play_card(card.clone());
+            }
+
+            replay.next_player();
+        }
+
+        manual.apply_hand_sizes(&view.hand_sizes());
+
+        assert_eq!(inference, manual);
+
+        // Every player's real hand is still possible
+        for (player, hand) in state.all_player_cards().iter().enumerate() {
+            assert_eq!(inference.possible(player).raw() & hand.raw(), hand.raw());
+        }
+    }
+}
@@ -0,0 +1,431 @@
+use std::cmp::Reverse;
+use std::time::{Duration, Instant};
+
+use rand::{seq::SliceRandom, Rng, RngCore};
+
+use crate::cards::{Card, CardCollection};
+
+#[cfg(not(feature = "nostats"))]
+use super::Results;
+use super::{state::State, Inference, Observation, Seat, Strategy};
+
+/// Exploration constant for choosing moves in the search tree
+const EXPLORATION: f64 = 0.7;
+
+/// Number of times to try dealing the unseen cards consistently with what has been
+/// inferred before ignoring passes
+const DEAL_ATTEMPTS: usize = 20;
+
+/// Limits on the search made for each move
+#[derive(Debug, Clone)]
+pub struct IsmctsLimits {
+    /// Number of games to play out
+    pub iterations: usize,
+    /// Time allowed, if limited
+    pub time: Option<Duration>,
+}
+
+impl Default for IsmctsLimits {
+    fn default() -> Self {
+        Self {
+            iterations: 5_000,
+            time: None,
+        }
+    }
+}
+
+/// Information set Monte Carlo tree search player. For each move it repeatedly deals the
+/// cards it can't see to the other players, consistently with what it has seen, and plays
+/// the game out with a strategy, building a tree of the moves with the best win rates
+#[derive(Debug, Clone)]
+pub struct Ismcts {
+    limits: IsmctsLimits,
+    /// Strategy used by every player when playing games out
+    policy: Strategy,
+}
+
+/// A move in the search tree
+#[derive(Debug)]
+struct Node {
+    /// Card played to reach the node, None for a pass
+    card: Option<Card>,
+    /// Player who made the move
+    player: usize,
+    /// Number of games played through the node
+    visits: u32,
+    /// Number of those games won by the player who made the move
+    wins: u32,
+    /// Number of times the move could have been chosen
+    avail: u32,
+    children: Vec<usize>,
+}
+
+impl Node {
+    fn new(card: Option<Card>, player: usize) -> Self {
+        Self {
+            card,
+            player,
+            visits: 0,
+            wins: 0,
+            avail: 1,
+            children: Vec::new(),
+        }
+    }
+
+    /// Upper confidence bound of the win rate used to choose between moves
+    fn ucb(&self) -> f64 {
+        self.wins as f64 / self.visits as f64
+            + EXPLORATION * ((self.avail as f64).ln() / self.visits as f64).sqrt()
+    }
+}
+
+impl Ismcts {
+    pub fn new(limits: IsmctsLimits, policy: Strategy) -> Self {
+        Self { limits, policy }
+    }
+
+    /// Searches for the best card to play, returning each card the player can play with
+    /// the number of games played after it and the fraction of them won
+    pub fn search(&self, view: &Observation, rng: &mut dyn RngCore) -> Vec<(Card, u32, f64)> {
+        let inference = view.inference();
+        let start = Instant::now();
+
+        let mut nodes = vec![Node::new(None, view.player())];
+
+        #[cfg(not(feature = "nostats"))]
+        let mut results = Results::new(view.player_count() as u8);
+
+        for _ in 0..self.limits.iterations {
+            if self.limits.time.is_some_and(|t| start.elapsed() >= t) {
+                break;
+            }
+
+            let state = view.determinise(deal(view, &inference, rng));
+
+            self.iterate(
+                &mut nodes,
+                state,
+                rng,
+                #[cfg(not(feature = "nostats"))]
+                &mut results,
+            );
+        }
+
+        let mut cards = nodes[0]
+            .children
+            .iter()
+            .map(|c| &nodes[*c])
+            .filter_map(|n| {
+                n.card
+                    .clone()
+                    .map(|card| (card, n.visits, n.wins as f64 / n.visits.max(1) as f64))
+            })
+            .collect::<Vec<_>>();
+
+        cards.sort_by_key(|c| Reverse(c.1));
+
+        cards
+    }
+
+    /// Plays a single game through the tree, adding a node, and records the winner
+    fn iterate(
+        &self,
+        nodes: &mut Vec<Node>,
+        mut state: State,
+        rng: &mut dyn RngCore,
+        #[cfg(not(feature = "nostats"))] results: &mut Results,
+    ) {
+        let mut path = vec![0];
+        let mut node = 0;
+        let mut winner = None;
+
+        // Choose moves down the tree until a new one is added
+        loop {
+            let player = state.cur_player();
+            let moves = legal_moves(&state);
+
+            let untried = moves
+                .iter()
+                .filter(|m| !nodes[node].children.iter().any(|c| nodes[*c].card == **m))
+                .collect::<Vec<_>>();
+
+            let expanded = !untried.is_empty();
+
+            let child = if expanded {
+                let card = (*untried.choose(rng).unwrap()).clone();
+
+                let child = nodes.len();
+
+                nodes.push(Node::new(card, player));
+                nodes[node].children.push(child);
+
+                child
+            } else {
+                // Choose between the moves possible in this deal
+                let children = nodes[node]
+                    .children
+                    .iter()
+                    .copied()
+                    .filter(|c| moves.contains(&nodes[*c].card))
+                    .collect::<Vec<_>>();
+
+                children.iter().for_each(|c| nodes[*c].avail += 1);
+
+                *children
+                    .iter()
+                    .max_by(|a, b| nodes[**a].ucb().total_cmp(&nodes[**b].ucb()))
+                    .unwrap()
+            };
+
+            path.push(child);
+
+            if let Some(card) = nodes[child].card.clone() {
+                state.play_card(card);
+
+                if state.cur_player_cards().is_empty() {
+                    winner = Some(player);
+                    break;
+                }
+            }
+
+            state.next_player();
+            node = child;
+
+            if expanded {
+                break;
+            }
+        }
+
+        let winner = winner.unwrap_or_else(|| {
+            self.play_out(
+                state,
+                rng,
+                #[cfg(not(feature = "nostats"))]
+                results,
+            )
+        });
+
+        for n in path {
+            let node = &mut nodes[n];
+
+            node.visits += 1;
+
+            if node.player == winner {
+                node.wins += 1;
+            }
+        }
+    }
+
+    /// Plays the game to the end with every player using the policy, returning the winner
+    fn play_out(
+        &self,
+        mut state: State,
+        rng: &mut dyn RngCore,
+        #[cfg(not(feature = "nostats"))] results: &mut Results,
+    ) -> usize {
+        loop {
+            let cards = self.policy.choose_cards_for(
+                &Observation::new(&state, &[]),
+                #[cfg(not(feature = "nostats"))]
+                results,
+            );
+
+            if !cards.is_empty() {
+                let card = cards
+                    .card_iterator()
+                    .nth(rng.gen_range(0..cards.len()))
+                    .unwrap();
+
+                state.play_card(card);
+
+                if state.cur_player_cards().is_empty() {
+                    return state.cur_player();
+                }
+            }
+
+            state.next_player();
+        }
+    }
+}
+
+impl Seat for Ismcts {
+    fn choose(&mut self, view: &Observation, rng: &mut dyn RngCore) -> Option<Card> {
+        let playable = view.playable_cards().0;
+
+        if playable.len() <= 1 {
+            // Nothing to choose
+            return playable.card_iterator().next();
+        }
+
+        self.search(view, rng)
+            .into_iter()
+            .next()
+            .map(|(card, _, _)| card)
+    }
+
+    fn name(&self) -> String {
+        "ISMCTS".to_string()
+    }
+}
+
+/// Returns the moves the player to move can make, None being a pass
+fn legal_moves(state: &State) -> Vec<Option<Card>> {
+    let playable = state.playable_cards().0;
+
+    if playable.is_empty() {
+        vec![None]
+    } else {
+        playable.card_iterator().map(Some).collect()
+    }
+}
+
+/// Deals the cards the observing player can't see to the other players at random, giving
+/// each player only cards they could hold and the right number of them
+fn deal(view: &Observation, inference: &Inference, rng: &mut dyn RngCore) -> Vec<CardCollection> {
+    let observer = view.player();
+    let players = view.player_count();
+    let sizes = view.hand_sizes();
+
+    let unseen = (0..players)
+        .filter(|p| *p != observer)
+        .fold(CardCollection::new(), |acc, p| {
+            CardCollection::new_from_raw(acc.raw() | inference.possible(p).raw())
+        });
+
+    for attempt in 0..=DEAL_ATTEMPTS {
+        // Passes are ignored on the last attempt so dealing can't fail
+        let strict = attempt < DEAL_ATTEMPTS;
+
+        let could_hold = |player: usize, card: &Card| {
+            player != observer && (!strict || inference.possible(player).contains(card.clone()))
+        };
+
+        // Deal the cards with the fewest possible holders first
+        let mut cards = unseen.card_iterator().collect::<Vec<_>>();
+        cards.shuffle(rng);
+        cards.sort_by_key(|c| (0..players).filter(|p| could_hold(*p, c)).count());
+
+        let mut hands = vec![CardCollection::new(); players];
+        hands[observer] = view.hand().clone();
+
+        let mut space = sizes.clone();
+        space[observer] = 0;
+
+        let dealt = cards.into_iter().all(|card| {
+            let holders = (0..players)
+                .filter(|p| space[*p] > 0 && could_hold(*p, &card))
+                .collect::<Vec<_>>();
+
+            // Players with more room are more likely to hold the card
+            match holders.choose_weighted(rng, |p| space[*p]) {
+                Ok(p) => {
+                    hands[*p].add(card);
+                    space[*p] -= 1;
+                    true
+                }
+                Err(_) => false,
+            }
+        });
+
+        if dealt {
+            return hands;
+        }
+    }
+
+    unreachable!("unseen cards could not be dealt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{play_game, sample_wins, tests::endgame_state, Move};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_deal() {
+        let state = endgame_state();
+        let history = [Move::Pass(0)];
+        let view = Observation::new(&state, &history);
+        let inference = view.inference();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..100 {
+            let hands = deal(&view, &inference, &mut rng);
+
+            assert_eq!(&hands[1], view.hand());
+
+            for (player, hand) in hands.iter().enumerate() {
+                assert_eq!(hand.len(), view.hand_size(player));
+                assert_eq!(hand.raw() & !inference.possible(player).raw(), 0);
+            }
+
+            // Every card is dealt once
+            let all = hands
+                .iter()
+                .fold(view.board().raw(), |acc, h| acc | h.raw());
+            assert_eq!(all.count_ones(), 52);
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let state = endgame_state();
+        let view = Observation::new(&state, &[]);
+
+        let ismcts = Ismcts::new(
+            IsmctsLimits {
+                iterations: 2_000,
+                time: None,
+            },
+            Strategy::Preferred,
+        );
+
+        let cards = ismcts.search(&view, &mut StdRng::seed_from_u64(1));
+
+        // Every playable card is tried, most played first
+        assert_eq!(cards.len(), view.playable_cards().0.len());
+        assert!(cards.windows(2).all(|w| w[0].1 >= w[1].1));
+        assert_eq!(cards.iter().map(|c| c.1).sum::<u32>(), 2_000);
+
+        // The chosen card is playable
+        let mut seat = ismcts.clone();
+        let card = seat.choose(&view, &mut StdRng::seed_from_u64(1)).unwrap();
+        assert!(view.playable_cards().0.contains(card));
+    }
+
+    #[test]
+    fn test_stronger_than_policy() {
+        // Against random players ISMCTS should win more often than the strategy it uses
+        // to play games out
+        let state = endgame_state();
+
+        let wins = |seat: Box<dyn Seat>| {
+            let mut rng = StdRng::seed_from_u64(2);
+            let mut seats: Vec<Box<dyn Seat>> = vec![
+                Box::new(Strategy::Dumb),
+                seat,
+                Box::new(Strategy::Dumb),
+                Box::new(Strategy::Dumb),
+            ];
+
+            (0..100)
+                .filter(|_| play_game(state.clone(), &mut seats, &mut rng).winner == 1)
+                .count()
+        };
+
+        let policy = wins(Box::new(Strategy::Dumb));
+        let ismcts = wins(Box::new(Ismcts::new(
+            IsmctsLimits {
+                iterations: 300,
+                time: None,
+            },
+            Strategy::Dumb,
+        )));
+
+        // Random games give the chance of the dumb player winning
+        let expected = sample_wins(state, Strategy::Dumb, 20_000)[1];
+
+        assert!((policy as f64 / 100.0 - expected).abs() < 0.15);
+        assert!(ismcts >= policy);
+    }
+}
@@ -0,0 +1,54 @@
+use std::str::FromStr;
+
+/// Errors found when parsing text made of `key: value` lines. Lines can also be separated
+/// by `;`, and blank lines and lines starting with `#` are ignored
+#[derive(Debug, PartialEq)]
+pub enum KeyValueError {
+    /// Line is not in the form `key: value`
+    Syntax(String),
+    /// Key is not recognised
+    UnknownKey(String),
+    /// Number is not valid
+    InvalidNumber(String),
+}
+
+impl std::fmt::Display for KeyValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyValueError::Syntax(line) => write!(f, "expected 'key: value' but found '{line}'"),
+            KeyValueError::UnknownKey(key) => write!(f, "unknown key '{key}'"),
+            KeyValueError::InvalidNumber(number) => write!(f, "invalid number '{number}'"),
+        }
+    }
+}
+
+impl std::error::Error for KeyValueError {}
+
+/// Calls a function with the lowercase key and the trimmed value of each `key: value` line
+pub(crate) fn parse_lines<E: From<KeyValueError>>(
+    s: &str,
+    mut f: impl FnMut(&str, &str) -> Result<(), E>,
+) -> Result<(), E> {
+    for line in s.split(['\n', ';']) {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| KeyValueError::Syntax(line.to_string()))?;
+
+        f(&key.trim().to_lowercase(), value.trim())?;
+    }
+
+    Ok(())
+}
+
+/// Parses the number in a value
+pub(crate) fn parse_number<T: FromStr>(value: &str) -> Result<T, KeyValueError> {
+    value
+        .parse()
+        .map_err(|_| KeyValueError::InvalidNumber(value.to_string()))
+}
@@ -1,13 +1,73 @@
+//! Game play, searching and results
+
+mod dfs;
+mod dot;
+mod estimate;
+mod evaluate;
+mod hint;
+mod inference;
+mod ismcts;
+mod key_value;
+mod observation;
 mod play;
+mod position;
+mod progress;
+mod replay;
 mod results;
+mod seat;
+mod split;
 mod state;
+mod stop;
 mod strategy;
+mod tournament;
+mod weights;
 
-use crate::cards::Card;
+use crate::cards::{Card, Rank, Suit};
 
+pub use dfs::{play_dfs, play_dfs_watched};
+pub use dot::{export_dot, DotLimits};
+pub use estimate::{estimate, games_per_second, sample_wins, sample_wins_with, Estimate};
+pub use evaluate::{evaluate_hand, EvaluateLimits, HandEvaluation};
+pub use hint::{hint, CardHint, HintLimits};
+pub use inference::Inference;
+pub use ismcts::{Ismcts, IsmctsLimits};
+pub use key_value::KeyValueError;
+pub use observation::Observation;
 pub use play::play;
-pub use results::Results;
+pub use position::{Position, PositionError};
+pub use progress::Progress;
+pub use replay::{find, replay, GameFilter, GameLine, GamePath, Move};
+pub use results::{PlayerResults, Results};
+pub use seat::{play_game, simulate, PlayedGame, Seat, Simulation};
+pub use split::Split;
 pub use state::State;
+pub use stop::Stop;
 pub use strategy::Strategy;
+pub use tournament::{
+    tournament, tournament_logged, Standing, TournamentConfig, TournamentGame, TournamentResults,
+    BASE_RATING,
+};
+pub use weights::{Weights, WeightsError};
+
+/// Bit mask of all valid card bits
+const ALL_CARDS: u64 = 0x1fff_1fff_1fff_1fff;
+
+const SEVEN_HEARTS: Card = Card::new_from_suit_rank(Suit::Hearts, Rank::Seven);
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Position, State};
+
+    /// Endgame position with four players and player 2 to move. Player 4 has two cards left
+    pub(crate) const ENDGAME: &str = "board: 2♥ 3♥ 4♥ 5♥ 6♥ 7♥ 8♥ 9♥ 10♥ J♥ 7♣ 8♣ 9♣ 10♣ 6♣ 5♣ 4♣ 3♣ 7♦ 8♦ 9♦ 6♦ 7♠ 8♠ 6♠ 5♠ 4♠;\
+        hand: A♥ Q♥ 2♣ 10♦ J♦ 3♠ 9♠ 10♠;\
+        hand: K♥ A♣ J♣ Q♦ K♦ 2♠ J♠;\
+        hand: Q♣ K♣ 5♦ 4♦ 3♦ A♠ Q♠ K♠;\
+        hand: 2♦ A♦;\
+        turn: 2";
 
-const SEVEN_HEARTS: Card = Card::new_from_elems(0, 6);
+    /// Returns the state for the endgame position
+    pub(crate) fn endgame_state() -> State {
+        State::new_from_position(&ENDGAME.parse::<Position>().unwrap())
+    }
+}
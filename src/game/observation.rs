@@ -0,0 +1,185 @@
+use crate::cards::CardCollection;
+
+use super::{state::State, Inference, Move};
+
+/// What the player to move knows about a game: their own hand, the board, the number of
+/// cards each player holds and the moves made so far. The other players' cards can't be
+/// reached through it.
+///
+/// ```compile_fail
+/// # use sevens::{cards::Deck, game::{Observation, State}};
+/// let state = State::new(4, Deck::new());
+/// let view = Observation::new(&state, &[]);
+///
+/// // The state behind the view is private
+/// let hands = view.state.all_player_cards();
+/// ```
+#[derive(Clone, Copy)]
+pub struct Observation<'a> {
+    state: &'a State,
+    history: &'a [Move],
+}
+
+impl<'a> Observation<'a> {
+    /// Creates the view of the player to move. The history is the moves made so far, which
+    /// is empty when playing every game as the search engines don't record moves
+    #[inline]
+    pub fn new(state: &'a State, history: &'a [Move]) -> Self {
+        Self { state, history }
+    }
+
+    /// Returns the observing player
+    #[inline]
+    pub fn player(&self) -> usize {
+        self.state.cur_player()
+    }
+
+    /// Returns the number of players
+    #[inline]
+    pub fn player_count(&self) -> usize {
+        self.state.player_count()
+    }
+
+    /// Returns the observing player's cards
+    #[inline]
+    pub fn hand(&self) -> &CardCollection {
+        self.state.cur_player_cards()
+    }
+
+    /// Returns the cards played
+    #[inline]
+    pub fn board(&self) -> &CardCollection {
+        self.state.board()
+    }
+
+    /// Returns the number of cards held by a player
+    #[inline]
+    pub fn hand_size(&self, player: usize) -> usize {
+        self.state.all_player_cards()[player].len()
+    }
+
+    /// Returns the number of cards held by each player
+    pub fn hand_sizes(&self) -> Vec<usize> {
+        self.state
+            .all_player_cards()
+            .iter()
+            .map(|c| c.len())
+            .collect()
+    }
+
+    /// Returns the moves made so far
+    #[inline]
+    pub fn history(&self) -> &'a [Move] {
+        self.history
+    }
+
+    /// Returns the number of goes a player has missed so far
+    pub fn passes(&self, player: usize) -> usize {
+        self.history
+            .iter()
+            .filter(|m| matches!(m, Move::Pass(p) if *p == player))
+            .count()
+    }
+
+    /// Returns the cards each player could hold as far as the observing player can tell
+    /// from the moves they have seen
+    pub fn inference(&self) -> Inference {
+        Inference::from_observation(self)
+    }
+
+    /// Returns a game the observing player can't tell apart from the real one, with the
+    /// other players holding the given cards instead
+    pub(crate) fn determinise(&self, hands: Vec<CardCollection>) -> State {
+        debug_assert_eq!(&hands[self.player()], self.hand());
+
+        self.state.with_hands(hands)
+    }
+
+    /// Returns the observing player's playable cards, and those of them which are no
+    /// consequence and in-sequence cards
+    #[inline]
+    pub fn playable_cards(&self) -> (CardCollection, CardCollection, CardCollection) {
+        // Only depends on the observing player's hand and the board
+        self.state.playable_cards()
+    }
+}
+
+impl PartialEq for Observation<'_> {
+    /// Views are equal if everything the player knows is the same
+    fn eq(&self, other: &Self) -> bool {
+        self.player() == other.player()
+            && self.hand() == other.hand()
+            && self.board() == other.board()
+            && self.hand_sizes() == other.hand_sizes()
+            && self.history == other.history
+    }
+}
+
+impl std::fmt::Debug for Observation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Observation")
+            .field("player", &self.player())
+            .field("hand", self.hand())
+            .field("board", self.board())
+            .field("hand_sizes", &self.hand_sizes())
+            .field("history", &self.history)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{tests::endgame_state, Position, Strategy};
+
+    /// Endgame position with the hands of players 1 and 3 swapped
+    const SWAPPED: &str = "board: 2♥ 3♥ 4♥ 5♥ 6♥ 7♥ 8♥ 9♥ 10♥ J♥ 7♣ 8♣ 9♣ 10♣ 6♣ 5♣ 4♣ 3♣ 7♦ 8♦ 9♦ 6♦ 7♠ 8♠ 6♠ 5♠ 4♠;\
+        hand: Q♣ K♣ 5♦ 4♦ 3♦ A♠ Q♠ K♠;\
+        hand: K♥ A♣ J♣ Q♦ K♦ 2♠ J♠;\
+        hand: A♥ Q♥ 2♣ 10♦ J♦ 3♠ 9♠ 10♠;\
+        hand: 2♦ A♦;\
+        turn: 2";
+
+    fn load(position: &str) -> State {
+        State::new_from_position(&position.parse::<Position>().unwrap())
+    }
+
+    #[test]
+    fn test_view() {
+        let state = endgame_state();
+        let history = [Move::Pass(0), Move::Pass(3)];
+        let view = Observation::new(&state, &history);
+
+        assert_eq!(view.player(), 1);
+        assert_eq!(view.player_count(), 4);
+        assert_eq!(view.hand(), &state.all_player_cards()[1]);
+        assert_eq!(view.board(), state.board());
+        assert_eq!(view.hand_sizes(), vec![8, 7, 8, 2]);
+        assert_eq!(view.passes(0), 1);
+        assert_eq!(view.passes(1), 0);
+        assert_eq!(view.passes(3), 1);
+    }
+
+    #[test]
+    fn test_hidden_hands() {
+        let state = endgame_state();
+        let swapped = load(SWAPPED);
+
+        // The games differ only in cards the player to move can't see
+        assert_ne!(state.all_player_cards(), swapped.all_player_cards());
+
+        let view = Observation::new(&state, &[]);
+        let swapped_view = Observation::new(&swapped, &[]);
+
+        assert_eq!(view, swapped_view);
+        assert_eq!(format!("{view:?}"), format!("{swapped_view:?}"));
+
+        // So every strategy must choose the same cards
+        for strategy in [Strategy::NoConsequence, Strategy::Preferred, Strategy::Dumb] {
+            assert_eq!(
+                strategy.candidates(&view),
+                strategy.candidates(&swapped_view)
+            );
+        }
+    }
+}
@@ -3,28 +3,57 @@ use std::cmp::Ordering;
 use futures::future::{BoxFuture, FutureExt};
 use tokio::task::JoinSet;
 
-use super::{state::State, Results, Strategy};
+#[cfg(feature = "trace")]
+use crate::cards::BoardLayout;
+
+use super::{state::State, Results, Split, Stop, Strategy};
+
+/// Plays all games from a state, spawning tokio tasks for branches of the game tree
+pub fn play(
+    state: State,
+    strategy: Strategy,
+    split: Split,
+    stop: Stop,
+) -> BoxFuture<'static, Results> {
+    play_weighted(state, strategy, split, stop, 1.0)
+}
 
-pub fn play(mut state: State, strategy: Strategy) -> BoxFuture<'static, Results> {
+/// Plays all games from a state which makes up the given fraction of the whole game tree
+fn play_weighted(
+    mut state: State,
+    strategy: Strategy,
+    split: Split,
+    stop: Stop,
+    mut weight: f64,
+) -> BoxFuture<'static, Results> {
     async move {
         let mut results = Results::new(state.player_count() as u8);
         let mut join_set = JoinSet::new();
 
+        if stop.is_stopped() {
+            results.record_stopped();
+            return results;
+        }
+
         #[cfg(feature = "trace")]
         println!("-- Start --");
 
         'outer: loop {
             'inner: {
                 #[cfg(feature = "trace")]
-                println!("Board: {:#}", state.board());
+                println!("Board:\n{}", BoardLayout::new(state.board()));
 
                 // Calculate playable cards
                 let (playable_cards, no_consequence_cards, sequence_cards) = state.playable_cards();
 
+                #[cfg(not(feature = "nostats"))]
+                let all_playable = playable_cards.clone();
+
                 let card_set = strategy.choose_cards(
                     no_consequence_cards,
                     sequence_cards,
                     playable_cards,
+                    state.cur_player_cards(),
                     #[cfg(not(feature = "nostats"))]
                     state.cur_player(),
                     #[cfg(not(feature = "nostats"))]
@@ -36,9 +65,7 @@ pub fn play(mut state: State, strategy: Strategy) -> BoxFuture<'static, Results>
                     Ordering::Less => {
                         // No cards to play
                         #[cfg(not(feature = "nostats"))]
-                        {
-                            state.add_miss();
-                        }
+                        state.add_miss();
 
                         break 'inner;
                     }
@@ -52,11 +79,19 @@ pub fn play(mut state: State, strategy: Strategy) -> BoxFuture<'static, Results>
 
                         let first_card = card_iter.next().unwrap();
 
-                        if state.cards_played() < 18 && !cfg!(feature = "trace") {
+                        // Each choice gets an equal share of this part of the tree
+                        weight /= card_set.len() as f64;
+
+                        if !cfg!(feature = "trace") && split.tokio(state.cards_played()) {
                             // Thread
                             for c in card_iter {
                                 let mut next_state = state.clone();
 
+                                #[cfg(not(feature = "nostats"))]
+                                next_state.add_held(&all_playable, &c);
+
+                                let stop = stop.clone();
+
                                 join_set.spawn(async move {
                                     // Play the card
                                     next_state.play_card(c);
@@ -65,7 +100,7 @@ pub fn play(mut state: State, strategy: Strategy) -> BoxFuture<'static, Results>
                                     next_state.next_player();
 
                                     // Play on
-                                    play(next_state, strategy).await
+                                    play_weighted(next_state, strategy, split, stop, weight).await
                                 });
                             }
                         } else {
@@ -73,6 +108,9 @@ pub fn play(mut state: State, strategy: Strategy) -> BoxFuture<'static, Results>
                             for c in card_iter {
                                 let mut next_state = state.clone();
 
+                                #[cfg(not(feature = "nostats"))]
+                                next_state.add_held(&all_playable, &c);
+
                                 #[cfg(feature = "trace")]
                                 println!(
                                     "Player {} playing {} with backtrack",
@@ -87,7 +125,14 @@ pub fn play(mut state: State, strategy: Strategy) -> BoxFuture<'static, Results>
                                 next_state.next_player();
 
                                 // Play on
-                                let result = play(next_state, strategy).await;
+                                let result = play_weighted(
+                                    next_state,
+                                    strategy,
+                                    split,
+                                    stop.clone(),
+                                    weight,
+                                )
+                                .await;
 
                                 results.add(result);
                             }
@@ -100,12 +145,16 @@ pub fn play(mut state: State, strategy: Strategy) -> BoxFuture<'static, Results>
                 #[cfg(feature = "trace")]
                 println!("Player {} playing {}", state.cur_player() + 1, card);
 
+                #[cfg(not(feature = "nostats"))]
+                state.add_held(&all_playable, &card);
+
                 // Play the card
                 state.play_card(card);
 
                 if state.cur_player_cards().is_empty() {
                     // Player has won
                     results.record_win(&state);
+                    results.record_covered(weight);
 
                     #[cfg(feature = "trace")]
                     println!("Win for player {}", state.cur_player() + 1);
@@ -0,0 +1,446 @@
+use crate::cards::{Card, CardCollection, CardCollectionParseError, CardParseError, Rank, Suit};
+
+use super::{
+    key_value::{parse_lines, parse_number},
+    KeyValueError, SEVEN_HEARTS,
+};
+
+/// A game position part way through a game
+///
+/// Positions are written as `key: value` lines (see [`KeyValueError`]):
+///
+/// ```text
+/// board: 7♥ 8♥ 7♣
+/// hand: A♥ 2♥ ...
+/// hand: 6♥ 9♥ ...
+/// turn: 2
+/// passes: 0 1
+/// ```
+///
+/// Cards are separated by spaces or commas. There is one `hand` line for each player in
+/// playing order. `turn` is the player to move and `passes` is the number of goes each player
+/// has missed so far (defaults to zero).
+#[derive(Debug, Clone)]
+pub struct Position {
+    /// Cards on the board
+    board: CardCollection,
+    /// Each player's cards
+    hands: Vec<CardCollection>,
+    /// Player to move
+    to_move: usize,
+    /// Number of missed goes for each player
+    passes: Vec<usize>,
+}
+
+impl Position {
+    /// Returns the cards on the board
+    pub fn board(&self) -> &CardCollection {
+        &self.board
+    }
+
+    /// Returns each player's cards
+    pub fn hands(&self) -> &[CardCollection] {
+        &self.hands
+    }
+
+    /// Returns the player to move
+    pub fn to_move(&self) -> usize {
+        self.to_move
+    }
+
+    /// Returns the number of missed goes for each player
+    pub fn passes(&self) -> &[usize] {
+        &self.passes
+    }
+
+    /// Checks the board is a layout which can be reached by playing from the 7♥
+    fn validate_board(&self) -> Result<(), PositionError> {
+        if self.board.is_empty() {
+            // The player to move must be about to play the 7♥
+            if !self.hands[self.to_move].contains(SEVEN_HEARTS) {
+                return Err(PositionError::IllegalBoard(format!(
+                    "player {} to move but does not hold the {SEVEN_HEARTS}",
+                    self.to_move + 1
+                )));
+            }
+
+            return Ok(());
+        }
+
+        if !self.board.contains(SEVEN_HEARTS) {
+            return Err(PositionError::IllegalBoard(format!(
+                "the {SEVEN_HEARTS} must be played first"
+            )));
+        }
+
+        for suit in Suit::iter() {
+            let ranks = (self.board.raw() >> (suit.elem() * 16)) & 0x1fff;
+
+            if ranks == 0 {
+                continue;
+            }
+
+            if !self
+                .board
+                .contains(Card::new_from_suit_rank(suit, Rank::Seven))
+            {
+                return Err(PositionError::IllegalBoard(format!(
+                    "{suit} cards played without the 7{suit}"
+                )));
+            }
+
+            let run = ranks >> ranks.trailing_zeros();
+
+            if run & (run + 1) != 0 {
+                return Err(PositionError::IllegalBoard(format!(
+                    "{suit} cards on the board are not in sequence"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Position {
+    type Err = PositionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut seen = CardCollection::new();
+        let mut board = CardCollection::new();
+        let mut hands = Vec::new();
+        let mut to_move = None;
+        let mut passes = None;
+
+        parse_lines::<PositionError>(s, |key, value| {
+            match key {
+                "board" => parse_cards(value, &mut seen, &mut board)?,
+                "hand" => {
+                    let mut hand = CardCollection::new();
+                    parse_cards(value, &mut seen, &mut hand)?;
+                    hands.push(hand);
+                }
+                "turn" => to_move = Some(parse_number(value)?),
+                "passes" => {
+                    passes = Some(
+                        value
+                            .split_whitespace()
+                            .map(parse_number)
+                            .collect::<Result<Vec<_>, _>>()?,
+                    )
+                }
+                key => return Err(KeyValueError::UnknownKey(key.to_string()).into()),
+            }
+
+            Ok(())
+        })?;
+
+        if hands.len() < 2 {
+            return Err(PositionError::TooFewPlayers(hands.len()));
+        }
+
+        let to_move = match to_move {
+            None => return Err(PositionError::MissingTurn),
+            Some(player) if player == 0 || player > hands.len() => {
+                return Err(PositionError::InvalidPlayer(player))
+            }
+            Some(player) => player - 1,
+        };
+
+        let passes = passes.unwrap_or_else(|| vec![0; hands.len()]);
+
+        if passes.len() != hands.len() {
+            return Err(PositionError::PassCount {
+                expected: hands.len(),
+                found: passes.len(),
+            });
+        }
+
+        if seen.len() != 52 {
+            return Err(PositionError::MissingCards(CardCollection::new_from_raw(
+                !seen.raw() & 0x1fff_1fff_1fff_1fff,
+            )));
+        }
+
+        if let Some(player) = hands.iter().position(|h| h.is_empty()) {
+            return Err(PositionError::GameOver(player));
+        }
+
+        let position = Self {
+            board,
+            hands,
+            to_move,
+            passes,
+        };
+
+        position.validate_board()?;
+
+        Ok(position)
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "board: {}", self.board)?;
+
+        for hand in &self.hands {
+            writeln!(f, "hand: {hand}")?;
+        }
+
+        writeln!(f, "turn: {}", self.to_move + 1)?;
+
+        let passes = self
+            .passes
+            .iter()
+            .map(|p| format!("{p}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        write!(f, "passes: {passes}")
+    }
+}
+
+/// Parses a list of cards in to a collection, checking for cards already seen
+fn parse_cards(
+    value: &str,
+    seen: &mut CardCollection,
+    cards: &mut CardCollection,
+) -> Result<(), PositionError> {
+    let parsed = value.parse::<CardCollection>().map_err(|e| match e {
+        CardCollectionParseError::InvalidCard { text, error, .. } => {
+            PositionError::InvalidCard(text, error)
+        }
+        CardCollectionParseError::Duplicate(card) => PositionError::DuplicateCard(card),
+    })?;
+
+    let repeated = CardCollection::new_from_raw(parsed.raw() & seen.raw());
+
+    if let Some(card) = repeated.card_iterator().next() {
+        return Err(PositionError::DuplicateCard(card));
+    }
+
+    *seen = CardCollection::new_from_raw(seen.raw() | parsed.raw());
+    *cards = CardCollection::new_from_raw(cards.raw() | parsed.raw());
+
+    Ok(())
+}
+
+/// Errors found when parsing a position
+#[derive(Debug, PartialEq)]
+pub enum PositionError {
+    /// Line is not a valid `key: value` line
+    KeyValue(KeyValueError),
+    /// Card is not valid
+    InvalidCard(String, CardParseError),
+    /// Card appears more than once
+    DuplicateCard(Card),
+    /// Cards not on the board or in any hand
+    MissingCards(CardCollection),
+    /// Not enough hands given
+    TooFewPlayers(usize),
+    /// Player to move is not valid
+    InvalidPlayer(usize),
+    /// Player to move not given
+    MissingTurn,
+    /// Wrong number of pass counts given
+    PassCount { expected: usize, found: usize },
+    /// Board can't be reached from the 7♥
+    IllegalBoard(String),
+    /// Player has no cards left
+    GameOver(usize),
+}
+
+impl std::fmt::Display for PositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PositionError::KeyValue(e) => write!(f, "{e}"),
+            PositionError::InvalidCard(card, e) => write!(f, "invalid card '{card}': {e}"),
+            PositionError::DuplicateCard(card) => write!(f, "{card} appears more than once"),
+            PositionError::MissingCards(cards) => write!(f, "cards not accounted for: {cards}"),
+            PositionError::TooFewPlayers(count) => {
+                write!(f, "at least 2 hands are required but {count} given")
+            }
+            PositionError::InvalidPlayer(player) => write!(f, "player {player} is not valid"),
+            PositionError::MissingTurn => write!(f, "player to move not given"),
+            PositionError::PassCount { expected, found } => {
+                write!(f, "{expected} pass counts are required but {found} given")
+            }
+            PositionError::IllegalBoard(reason) => write!(f, "illegal board: {reason}"),
+            PositionError::GameOver(player) => {
+                write!(f, "player {} has no cards left", player + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PositionError {}
+
+impl From<KeyValueError> for PositionError {
+    fn from(e: KeyValueError) -> Self {
+        PositionError::KeyValue(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cards::Deck;
+    use crate::game::State;
+
+    use super::*;
+
+    /// Builds a position string with the cards not on the board dealt to three players
+    fn position_string(board: &str, turn: usize) -> String {
+        let board_cards = board
+            .split_whitespace()
+            .map(|c| c.parse::<Card>().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut hands = vec![String::from("hand:"); 3];
+
+        Deck::new()
+            .iter()
+            .filter(|c| !board_cards.contains(c))
+            .enumerate()
+            .for_each(|(i, c)| hands[i % 3] += &format!(" {c}"));
+
+        format!("board: {board}\n{}\nturn: {turn}", hands.join("\n"))
+    }
+
+    fn position(board: &str, turn: usize) -> Result<Position, PositionError> {
+        position_string(board, turn).parse()
+    }
+
+    #[test]
+    fn test_valid() {
+        let position = position("7♥ 8♥ 7♣ 6♣ 5♣", 2).unwrap();
+
+        assert_eq!(position.hands().len(), 3);
+        assert_eq!(position.to_move(), 1);
+        assert_eq!(position.board().len(), 5);
+        assert_eq!(position.hands()[2].len(), 15);
+        assert_eq!(position.passes(), &[0, 0, 0]);
+
+        // Check round trip
+        let position2 = format!("{position}").parse::<Position>().unwrap();
+
+        assert_eq!(position2.board(), position.board());
+        assert_eq!(position2.hands(), position.hands());
+        assert_eq!(position2.to_move(), position.to_move());
+        assert_eq!(position2.passes(), position.passes());
+    }
+
+    #[test]
+    fn test_single_line() {
+        let string = position_string("7♥", 1).replace('\n', ";") + "; passes: 1 2 0";
+        let position = string.parse::<Position>().unwrap();
+
+        assert_eq!(position.passes(), &[1, 2, 0]);
+
+        // The state remembers the passes made before the position
+        let state = State::new_from_position(&position);
+        assert_eq!(
+            (0..3).map(|p| state.start_passes(p)).collect::<Vec<_>>(),
+            [1, 2, 0]
+        );
+    }
+
+    #[test]
+    fn test_commas() {
+        let string = position_string("7♥ 8♥ 7♣", 1);
+        let position = string.parse::<Position>().unwrap();
+
+        let position2 = string
+            .replace(" 8♥", ", 8♥")
+            .replace(" 7♣", ",7♣")
+            .parse::<Position>()
+            .unwrap();
+
+        assert_eq!(position2.board(), position.board());
+        assert_eq!(position2.hands(), position.hands());
+    }
+
+    #[test]
+    fn test_start() {
+        // 7♥ is dealt to the first player
+        assert!(position("", 1).is_ok());
+
+        assert!(matches!(
+            position("", 2),
+            Err(PositionError::IllegalBoard(_))
+        ));
+    }
+
+    #[test]
+    fn test_illegal_board() {
+        // No 7♥
+        assert!(matches!(
+            position("7♣", 1),
+            Err(PositionError::IllegalBoard(_))
+        ));
+
+        // No 7 in suit
+        assert!(matches!(
+            position("7♥ 6♣", 1),
+            Err(PositionError::IllegalBoard(_))
+        ));
+
+        // Gap in sequence
+        assert!(matches!(
+            position("7♥ 8♥ 10♥", 1),
+            Err(PositionError::IllegalBoard(_))
+        ));
+    }
+
+    #[test]
+    fn test_errors() {
+        let seven_spades = Card::new(&'♠', "7").unwrap();
+
+        assert_eq!(
+            (position_string("7♥", 1) + "\nboard: 7♠")
+                .parse::<Position>()
+                .unwrap_err(),
+            PositionError::DuplicateCard(seven_spades.clone())
+        );
+
+        assert_eq!(
+            position_string("7♥", 1)
+                .replace(" 7♠", "")
+                .parse::<Position>()
+                .unwrap_err(),
+            PositionError::MissingCards(CardCollection::new_single(seven_spades))
+        );
+
+        // Repeated within a list
+        assert_eq!(
+            "board: 7♥, 7♥".parse::<Position>().unwrap_err(),
+            PositionError::DuplicateCard(Card::new(&'♥', "7").unwrap())
+        );
+
+        assert_eq!(
+            (position_string("7♥", 1) + "\nboard: 7X")
+                .parse::<Position>()
+                .unwrap_err(),
+            PositionError::InvalidCard("7X".to_string(), CardParseError::InvalidSuit('X'))
+        );
+
+        assert_eq!(
+            position("7♥", 4).unwrap_err(),
+            PositionError::InvalidPlayer(4)
+        );
+
+        assert_eq!(
+            "board: 7♥\nfoo: bar".parse::<Position>().unwrap_err(),
+            PositionError::KeyValue(KeyValueError::UnknownKey("foo".to_string()))
+        );
+
+        assert_eq!(
+            (position_string("7♥", 1) + "\npasses: 1 2")
+                .parse::<Position>()
+                .unwrap_err(),
+            PositionError::PassCount {
+                expected: 3,
+                found: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use std::sync::{Arc, Mutex};
+
+use super::Results;
+
+/// Handle used to watch the results of a running search. Searches add their results as
+/// they go. Clones share the same results
+#[derive(Debug, Clone)]
+pub struct Progress(Arc<Mutex<Results>>);
+
+impl Progress {
+    pub fn new(players: u8) -> Self {
+        Self(Arc::new(Mutex::new(Results::new(players))))
+    }
+
+    /// Adds results from part of a search
+    pub(crate) fn add(&self, results: Results) {
+        self.0.lock().unwrap().add(results);
+    }
+
+    /// Returns a copy of the results so far
+    pub fn results(&self) -> Results {
+        self.0.lock().unwrap().clone()
+    }
+}
@@ -0,0 +1,454 @@
+use std::cmp::Ordering;
+
+use crate::cards::{BoardLayout, Card, DisplaySymbols, Symbols};
+
+#[cfg(not(feature = "nostats"))]
+use super::Results;
+use super::{state::State, Observation, Strategy};
+
+/// A single move in a game
+#[derive(Debug, Clone, PartialEq)]
+pub enum Move {
+    /// Player played a card
+    Play(usize, Card),
+    /// Player missed a go
+    Pass(usize),
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_symbols(f, Symbols::Unicode)
+    }
+}
+
+impl DisplaySymbols for Move {
+    fn fmt_symbols(&self, f: &mut std::fmt::Formatter<'_>, symbols: Symbols) -> std::fmt::Result {
+        match self {
+            Move::Play(player, card) => f.write_str(&format!(
+                "Player {} plays {}",
+                player + 1,
+                card.display(symbols)
+            )),
+            Move::Pass(player) => f.write_str(&format!("Player {} passes", player + 1)),
+        }
+    }
+}
+
+/// Branch choices taken at each point in a game where more than one card could be played
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GamePath(Vec<usize>);
+
+impl GamePath {
+    /// Returns the branch choices
+    pub fn choices(&self) -> &[usize] {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for GamePath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::default());
+        }
+
+        s.split('.')
+            .map(|elem| {
+                elem.parse::<usize>()
+                    .map_err(|_| format!("Invalid branch choice '{elem}' in game path"))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+    }
+}
+
+impl std::fmt::Display for GamePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = self
+            .0
+            .iter()
+            .map(|c| format!("{c}"))
+            .collect::<Vec<_>>()
+            .join(".");
+
+        f.write_str(&string)
+    }
+}
+
+/// Conditions a game must meet when searching for a game line
+#[derive(Debug, Default)]
+pub struct GameFilter {
+    /// Player that must win the game
+    pub winner: Option<usize>,
+    /// Maximum number of goes the winner may miss
+    pub max_misses: Option<usize>,
+}
+
+impl GameFilter {
+    fn matches(&self, line: &GameLine) -> bool {
+        if let Some(winner) = self.winner {
+            if line.winner != winner {
+                return false;
+            }
+        }
+
+        if let Some(max_misses) = self.max_misses {
+            if line.misses(line.winner) > max_misses {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// A complete game from a starting state
+#[derive(Debug)]
+pub struct GameLine {
+    /// Starting state
+    start: State,
+    /// Branch choices taken
+    path: GamePath,
+    /// Moves made
+    moves: Vec<Move>,
+    /// Winning player
+    winner: usize,
+}
+
+impl GameLine {
+    /// Returns the starting state
+    pub fn start(&self) -> &State {
+        &self.start
+    }
+
+    /// Returns the branch choices taken
+    pub fn path(&self) -> &GamePath {
+        &self.path
+    }
+
+    /// Returns the moves made
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Returns the winning player
+    pub fn winner(&self) -> usize {
+        self.winner
+    }
+
+    /// Returns the number of goes missed by a player
+    pub fn misses(&self, player: usize) -> usize {
+        self.moves
+            .iter()
+            .filter(|m| matches!(m, Move::Pass(p) if *p == player))
+            .count()
+    }
+
+    /// Prints the game move by move with the board after each card is played
+    pub fn print(&self, symbols: Symbols) {
+        let mut state = self.start.clone();
+
+        for m in &self.moves {
+            println!("{}", m.display(symbols));
+
+            if let Move::Play(_, card) = m {
+                state.play_card(card.clone());
+                BoardLayout::new(state.board()).symbols(symbols).print("  ");
+            }
+        }
+
+        println!("Win for player {}", self.winner + 1);
+
+        for player in 0..state.player_count() {
+            println!(
+                "  Player {}: {} missed goes",
+                player + 1,
+                self.misses(player)
+            );
+        }
+
+        println!("Game path: {}", self.path);
+    }
+}
+
+impl std::fmt::Display for GameLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for m in &self.moves {
+            writeln!(f, "{m}")?;
+        }
+
+        write!(f, "Win for player {}", self.winner + 1)
+    }
+}
+
+/// Replays the game line given by a path of branch choices. Once the path is exhausted the
+/// first choice is always taken
+pub fn replay(mut state: State, strategy: Strategy, path: &GamePath) -> Result<GameLine, String> {
+    let start = state.clone();
+    #[cfg(not(feature = "nostats"))]
+    let mut results = Results::new(state.player_count() as u8);
+    let mut moves = Vec::new();
+    let mut taken = Vec::new();
+    let mut choices = path.choices().iter();
+
+    loop {
+        let card_set = strategy.choose_cards_for(
+            &Observation::new(&state, &moves),
+            #[cfg(not(feature = "nostats"))]
+            &mut results,
+        );
+
+        let card = match card_set.len().cmp(&1) {
+            Ordering::Less => None,
+            Ordering::Equal => card_set.card_iterator().next(),
+            Ordering::Greater => {
+                let choice = choices.next().cloned().unwrap_or(0);
+
+                if choice >= card_set.len() {
+                    return Err(format!(
+                        "Branch choice {choice} at decision {} is out of range, only {} cards can be played ({card_set})",
+                        taken.len() + 1,
+                        card_set.len()
+                    ));
+                }
+
+                taken.push(choice);
+
+                card_set.card_iterator().nth(choice)
+            }
+        };
+
+        match card {
+            Some(card) => {
+                moves.push(Move::Play(state.cur_player(), card.clone()));
+
+                state.play_card(card);
+
+                if state.cur_player_cards().is_empty() {
+                    break;
+                }
+            }
+            None => moves.push(Move::Pass(state.cur_player())),
+        }
+
+        state.next_player();
+    }
+
+    Ok(GameLine {
+        start,
+        path: GamePath(taken),
+        moves,
+        winner: state.cur_player(),
+    })
+}
+
+/// Searches the game tree depth first for the first game matching a filter
+pub fn find(state: State, strategy: Strategy, filter: &GameFilter) -> Option<GameLine> {
+    let mut finder = Finder {
+        start: state.clone(),
+        strategy,
+        filter,
+        #[cfg(not(feature = "nostats"))]
+        results: Results::new(state.player_count() as u8),
+        path: Vec::new(),
+        moves: Vec::new(),
+    };
+
+    finder.search(state)
+}
+
+struct Finder<'a> {
+    start: State,
+    strategy: Strategy,
+    filter: &'a GameFilter,
+    #[cfg(not(feature = "nostats"))]
+    results: Results,
+    path: Vec<usize>,
+    moves: Vec<Move>,
+}
+
+impl Finder<'_> {
+    fn search(&mut self, mut state: State) -> Option<GameLine> {
+        let path_len = self.path.len();
+        let moves_len = self.moves.len();
+
+        let found = loop {
+            let card_set = self.strategy.choose_cards_for(
+                &Observation::new(&state, &self.moves),
+                #[cfg(not(feature = "nostats"))]
+                &mut self.results,
+            );
+
+            match card_set.len().cmp(&1) {
+                Ordering::Less => self.moves.push(Move::Pass(state.cur_player())),
+                Ordering::Equal => {
+                    let card = card_set.card_iterator().next().unwrap();
+
+                    let found = self.play(&mut state, card);
+
+                    if found.is_some() || state.cur_player_cards().is_empty() {
+                        break found;
+                    }
+                }
+                Ordering::Greater => {
+                    // Try each card in turn
+                    let mut found = None;
+
+                    for (choice, card) in card_set.card_iterator().enumerate() {
+                        let mut next_state = state.clone();
+
+                        self.path.push(choice);
+
+                        found = self.play(&mut next_state, card.clone());
+
+                        if found.is_none() && !next_state.cur_player_cards().is_empty() {
+                            next_state.next_player();
+                            found = self.search(next_state);
+                        }
+
+                        if found.is_some() {
+                            break;
+                        }
+
+                        self.path.pop();
+                        self.moves.pop();
+                    }
+
+                    break found;
+                }
+            }
+
+            state.next_player();
+        };
+
+        if found.is_none() {
+            // Unwind
+            self.path.truncate(path_len);
+            self.moves.truncate(moves_len);
+        }
+
+        found
+    }
+
+    /// Plays a card, returning the game line if this wins a game matching the filter. The
+    /// game has ended if the current player has no cards left
+    fn play(&mut self, state: &mut State, card: Card) -> Option<GameLine> {
+        self.moves
+            .push(Move::Play(state.cur_player(), card.clone()));
+
+        state.play_card(card);
+
+        if state.cur_player_cards().is_empty() {
+            let line = GameLine {
+                start: self.start.clone(),
+                path: GamePath(self.path.clone()),
+                moves: self.moves.clone(),
+                winner: state.cur_player(),
+            };
+
+            if self.filter.matches(&line) {
+                return Some(line);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::tests::endgame_state;
+
+    fn card(s: &str) -> Card {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_game_path() {
+        let path = "0.2.1".parse::<GamePath>().unwrap();
+
+        assert_eq!(path.choices(), &[0, 2, 1]);
+        assert_eq!(format!("{path}"), "0.2.1");
+
+        assert_eq!("".parse::<GamePath>().unwrap(), GamePath::default());
+        assert!("0.x.1".parse::<GamePath>().is_err());
+        assert!("0..1".parse::<GamePath>().is_err());
+    }
+
+    #[test]
+    fn test_replay() {
+        // Player 2 can only play the J♣, then player 3 chooses between the Q♣ and the 5♦
+        let line = replay(endgame_state(), Strategy::Dumb, &"1".parse().unwrap()).unwrap();
+
+        assert_eq!(line.moves[0], Move::Play(1, card("J♣")));
+        assert_eq!(line.moves[1], Move::Play(2, card("5♦")));
+        assert_eq!(line.path().choices()[0], 1);
+
+        // The game is played to the end
+        assert!(matches!(line.moves.last(), Some(Move::Play(p, _)) if *p == line.winner));
+
+        let plays = line
+            .moves
+            .iter()
+            .filter(|m| matches!(m, Move::Play(p, _) if *p == line.winner))
+            .count();
+        assert_eq!(plays, line.start.all_player_cards()[line.winner].len());
+
+        // The full path taken gives the same game
+        let again = replay(endgame_state(), Strategy::Dumb, line.path()).unwrap();
+        assert_eq!(again.moves, line.moves);
+
+        // Choices out of range are rejected
+        assert!(replay(endgame_state(), Strategy::Dumb, &"2".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        // Player 1 can't win once player 4 is down to two cards
+        for player in 0..4 {
+            let filter = GameFilter {
+                winner: Some(player),
+                max_misses: None,
+            };
+
+            let line = find(endgame_state(), Strategy::Dumb, &filter);
+
+            assert_eq!(line.is_some(), player != 0);
+
+            if let Some(line) = line {
+                assert_eq!(line.winner, player);
+                assert!(matches!(line.moves.last(), Some(Move::Play(p, _)) if *p == player));
+
+                // Replaying the path gives the same game
+                let again = replay(endgame_state(), Strategy::Dumb, line.path()).unwrap();
+                assert_eq!(again.moves, line.moves);
+            }
+        }
+
+        // Limiting the missed goes finds a game within the limit, or none
+        let misses = find(
+            endgame_state(),
+            Strategy::Dumb,
+            &GameFilter {
+                winner: Some(3),
+                max_misses: None,
+            },
+        )
+        .unwrap()
+        .misses(3);
+
+        for max_misses in 0..=misses {
+            let filter = GameFilter {
+                winner: Some(3),
+                max_misses: Some(max_misses),
+            };
+
+            match find(endgame_state(), Strategy::Dumb, &filter) {
+                Some(line) => assert!(line.misses(3) <= max_misses),
+                None => assert!(max_misses < misses),
+            }
+        }
+    }
+}
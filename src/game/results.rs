@@ -2,13 +2,17 @@
 use std::cmp::{max, Ordering};
 
 #[cfg(not(feature = "nostats"))]
-use crate::cards::CardCollection;
-use crate::Args;
+use colored::*;
+
+use crate::cards::Symbols;
+#[cfg(not(feature = "nostats"))]
+use crate::cards::{Card, CardCollection, Rank, Suit};
 use numformat::NumFormat;
 
-use super::State;
+use super::{State, Strategy};
 
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlayerResults {
     wins: usize,
     #[cfg(not(feature = "nostats"))]
@@ -21,6 +25,25 @@ pub struct PlayerResults {
     multi: [usize; 4],
 }
 
+impl PlayerResults {
+    /// Returns the number of games won
+    pub fn wins(&self) -> usize {
+        self.wins
+    }
+
+    #[cfg(not(feature = "nostats"))]
+    /// Returns the highest number of missed goes in a game won by the player
+    pub fn best_win(&self) -> usize {
+        self.best_win
+    }
+
+    #[cfg(not(feature = "nostats"))]
+    /// Returns the number of goes missed
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+}
+
 impl std::ops::AddAssign<&PlayerResults> for PlayerResults {
     fn add_assign(&mut self, other: &Self) {
         self.wins += other.wins;
@@ -46,10 +69,23 @@ impl std::ops::AddAssign<&PlayerResults> for PlayerResults {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Results {
     player_results: Vec<PlayerResults>,
     games: usize,
+    /// Fraction of the game tree searched, each choice at a branch point weighted equally
+    covered: f64,
+    /// Search was stopped before the whole game tree was searched
+    stopped: bool,
+    /// Number of times each card was playable but not played, summed over the games
+    #[cfg(not(feature = "nostats"))]
+    #[cfg_attr(feature = "serde", serde(with = "card_counts"))]
+    card_held: [usize; 52],
+    /// Number of passes caused solely by each card not being played, summed over the games
+    #[cfg(not(feature = "nostats"))]
+    #[cfg_attr(feature = "serde", serde(with = "card_counts"))]
+    card_pass_blocks: [usize; 52],
 }
 
 impl Results {
@@ -57,12 +93,23 @@ impl Results {
         Self {
             player_results: vec![PlayerResults::default(); players as usize],
             games: 0,
+            covered: 0.0,
+            stopped: false,
+            #[cfg(not(feature = "nostats"))]
+            card_held: [0; 52],
+            #[cfg(not(feature = "nostats"))]
+            card_pass_blocks: [0; 52],
         }
     }
 
     #[inline]
     #[cfg(not(feature = "nostats"))]
-    pub fn update_stats_for(&mut self, player: usize, cards: &CardCollection, pref_rank: u8) {
+    pub(crate) fn update_stats_for(
+        &mut self,
+        player: usize,
+        cards: &CardCollection,
+        pref_rank: u8,
+    ) {
         match cards.len().cmp(&1) {
             Ordering::Less => self.player_results[player].misses += 1,
             Ordering::Equal => self.player_results[player].single[pref_rank as usize] += 1,
@@ -71,7 +118,7 @@ impl Results {
     }
 
     #[inline]
-    pub fn record_win(&mut self, state: &State) {
+    pub(crate) fn record_win(&mut self, state: &State) {
         self.player_results[state.cur_player()].wins += 1;
 
         #[cfg(not(feature = "nostats"))]
@@ -80,33 +127,92 @@ impl Results {
                 self.player_results[state.cur_player()].best_win,
                 state.get_misses(),
             );
+
+            // Card counts are carried along the line of play so count once per game
+            self.card_held
+                .iter_mut()
+                .zip(state.card_held().iter())
+                .for_each(|(a, b)| *a += *b as usize);
+
+            self.card_pass_blocks
+                .iter_mut()
+                .zip(state.card_pass_blocks().iter())
+                .for_each(|(a, b)| *a += *b as usize);
         }
 
         self.games += 1;
     }
 
+    #[inline]
+    /// Records a part of the game tree as searched
+    pub(crate) fn record_covered(&mut self, fraction: f64) {
+        self.covered += fraction;
+    }
+
+    /// Records that the search was stopped early
+    pub(crate) fn record_stopped(&mut self) {
+        self.stopped = true;
+    }
+
     #[inline]
     pub fn games(&self) -> usize {
         self.games
     }
 
+    /// Returns true if the whole game tree was searched
+    pub fn is_complete(&self) -> bool {
+        !self.stopped
+    }
+
+    /// Returns the fraction of the game tree searched, each choice at a branch point
+    /// weighted equally
+    pub fn coverage(&self) -> f64 {
+        self.covered
+    }
+
     pub fn player_results(&self) -> &Vec<PlayerResults> {
         &self.player_results
     }
 
     pub fn add(&mut self, other: Results) {
         self.games += other.games;
+        self.covered += other.covered;
+        self.stopped |= other.stopped;
 
         self.player_results
             .iter_mut()
             .zip(other.player_results.iter())
             .for_each(|(a, b)| *a += b);
+
+        #[cfg(not(feature = "nostats"))]
+        {
+            self.card_held
+                .iter_mut()
+                .zip(other.card_held.iter())
+                .for_each(|(a, b)| *a += b);
+
+            self.card_pass_blocks
+                .iter_mut()
+                .zip(other.card_pass_blocks.iter())
+                .for_each(|(a, b)| *a += b);
+        }
     }
 
-    pub fn print(&self, args: &Args) {
+    /// Prints the results for games played with a strategy
+    pub fn print(&self, strategy: Strategy, symbols: Symbols) {
+        #[cfg(feature = "nostats")]
+        let _ = (strategy, symbols);
+
+        if !self.is_complete() {
+            println!(
+                "Results are INCOMPLETE: search stopped after covering {:.2}% of the game tree",
+                self.coverage() * 100.0
+            );
+        }
+
         println!("Games finished: {}", self.games().num_format());
 
-        let player_str = (1..=args.player_count)
+        let player_str = (1..=self.player_results.len())
             .map(|p| format!("{p}"))
             .collect::<Vec<_>>();
 
@@ -117,16 +223,16 @@ impl Results {
             // Print plays
             print!("Plays    {:<player_str_len$}:", "");
 
-            for i in 0..=args.strategy.max_pref_rank() {
-                print!(" {:>12} >1", args.strategy.pref_rank_desc(i));
-                print!(" {:>13} 1", args.strategy.pref_rank_desc(i));
+            for i in 0..=strategy.max_pref_rank() {
+                print!(" {:>12} >1", strategy.pref_rank_desc(i));
+                print!(" {:>13} 1", strategy.pref_rank_desc(i));
             }
             println!(" {:>15}", "Missed Goes");
 
             for (i, player_results) in self.player_results().iter().enumerate() {
                 print!("  Player {:<player_str_len$}:", player_str[i]);
 
-                for i in 0..=args.strategy.max_pref_rank() {
+                for i in 0..=strategy.max_pref_rank() {
                     print!(
                         " {:>15} {:>15}",
                         player_results.multi[i as usize].num_format(),
@@ -156,7 +262,7 @@ impl Results {
         let pcts_len = pcts.iter().map(|s| s.len()).max().unwrap();
 
         println!("Wins:");
-        for i in 0..args.player_count as usize {
+        for i in 0..self.player_results.len() {
             print!(
                 "  Player {:<player_str_len$}: {:>wins_len$} {:>pcts_len$}",
                 player_str[i], wins[i], pcts[i]
@@ -170,5 +276,202 @@ impl Results {
 
             println!();
         }
+
+        #[cfg(not(feature = "nostats"))]
+        {
+            // Print card heat maps
+            print_card_heat_map("Blocks (playable but held)", &self.card_held, symbols);
+            print_card_heat_map(
+                "Passes (sole missing card)",
+                &self.card_pass_blocks,
+                symbols,
+            );
+        }
+    }
+}
+
+#[cfg(not(feature = "nostats"))]
+/// Prints per-card counts as a grid of suits by ranks with each card's share of the total
+fn print_card_heat_map(title: &str, counts: &[usize; 52], symbols: Symbols) {
+    let total = counts.iter().sum::<usize>();
+    let max = counts.iter().max().cloned().unwrap_or(0);
+    let shares = card_shares(counts);
+
+    println!("{title}: {}", total.num_format());
+
+    for suit in Suit::iter() {
+        print!(" ");
+
+        for rank in Rank::iter() {
+            let card = Card::new_from_suit_rank(suit, rank);
+            let count = counts[card.index()];
+
+            let heat = format!("{:>5.1}", shares[card.index()]);
+
+            // Colour by count relative to the worst card
+            let heat = if count == 0 {
+                heat.dimmed()
+            } else {
+                match (count * 4) / max {
+                    0 => heat.normal(),
+                    1 => heat.green(),
+                    2 => heat.yellow(),
+                    _ => heat.red().bold(),
+                }
+            };
+
+            print!(" {}{heat}", card.coloured(symbols));
+        }
+
+        println!();
+    }
+}
+
+#[cfg(not(feature = "nostats"))]
+/// Returns each card's percentage of the total of the per-card counts
+fn card_shares(counts: &[usize; 52]) -> [f64; 52] {
+    let total = counts.iter().sum::<usize>();
+
+    counts.map(|count| {
+        if total == 0 {
+            0f64
+        } else {
+            (count as f64 / total as f64) * 100f64
+        }
+    })
+}
+
+/// Serialises per-card counts as a list (serde only derives arrays up to 32 elements)
+#[cfg(all(feature = "serde", not(feature = "nostats")))]
+pub(super) mod card_counts {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, T: Serialize>(
+        counts: &[T; 52],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(counts)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<[T; 52], D::Error> {
+        Vec::<T>::deserialize(deserializer)?
+            .try_into()
+            .map_err(|v: Vec<T>| D::Error::invalid_length(v.len(), &"52 card counts"))
+    }
+}
+
+#[cfg(all(test, any(feature = "serde", not(feature = "nostats"))))]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "nostats"))]
+    #[test]
+    fn test_card_stats() {
+        use crate::game::{play, play_dfs, Position, Split, Stop};
+
+        let card = |s: &str| s.parse::<Card>().unwrap();
+
+        // Player 1 can play the 5♣ or the K♠. Playing the K♠ first, or the K♠ before the
+        // 3♣ after the 5♣ and 4♣, leaves player 2 waiting on a single club. After the 5♣
+        // player 1 chooses again, so the K♠ held back there is in two games
+        let position = "board: A♥ 2♥ 3♥ 4♥ 5♥ 6♥ 7♥ 8♥ 9♥ 10♥ J♥ Q♥ K♥ \
+                A♦ 2♦ 3♦ 4♦ 5♦ 6♦ 7♦ 8♦ 9♦ 10♦ J♦ Q♦ K♦ \
+                A♠ 2♠ 3♠ 4♠ 5♠ 6♠ 7♠ 8♠ 9♠ 10♠ J♠ Q♠ 6♣ 7♣ 8♣ 9♣ 10♣ J♣ Q♣ K♣;\
+            hand: 5♣ 3♣ K♠;\
+            hand: 4♣ 2♣ A♣;\
+            turn: 1"
+            .parse::<Position>()
+            .unwrap();
+
+        let results = play_dfs(
+            State::new_from_position(&position),
+            Strategy::Dumb,
+            Split::default(),
+            Stop::new(),
+        );
+
+        assert_eq!(results.games(), 3);
+        assert_eq!(results.player_results()[0].wins(), 3);
+        assert_eq!(results.player_results()[1].misses(), 2);
+
+        // The K♠ is held back once in two games and twice in the third, the 5♣ and 3♣
+        // once each
+        let mut held = [0; 52];
+        held[card("K♠").index()] = 3;
+        held[card("5♣").index()] = 1;
+        held[card("3♣").index()] = 1;
+        assert_eq!(results.card_held, held);
+
+        // Player 2 passes once waiting on the 5♣ and once on the 3♣
+        let mut pass_blocks = [0; 52];
+        pass_blocks[card("5♣").index()] = 1;
+        pass_blocks[card("3♣").index()] = 1;
+        assert_eq!(results.card_pass_blocks, pass_blocks);
+
+        // The asynchronous engine counts the same
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let results2 = runtime.block_on(play(
+            State::new_from_position(&position),
+            Strategy::Dumb,
+            Split::default(),
+            Stop::new(),
+        ));
+        assert_eq!(results2.card_held, held);
+        assert_eq!(results2.card_pass_blocks, pass_blocks);
+
+        let shares = card_shares(&results.card_held);
+        assert_eq!(shares[card("K♠").index()], 60.0);
+        assert_eq!(shares[card("5♣").index()], 20.0);
+        assert_eq!(shares.iter().sum::<f64>(), 100.0);
+        assert_eq!(card_shares(&[0; 52]), [0.0; 52]);
+
+        // A 7 blocks a player holding the 6 or the 8
+        let position = "board: 7♥;\
+            hand: 6♦ 8♦ 2♠;\
+            hand: A♥ 2♥ 3♥ 4♥ 5♥ 6♥ 8♥ 9♥ 10♥ J♥ Q♥ K♥ A♣ 2♣ 3♣ 4♣ 5♣ 6♣ 7♣ 8♣ 9♣ 10♣ \
+                J♣ Q♣ K♣ A♦ 2♦ 3♦ 4♦ 5♦ 7♦ 9♦ 10♦ J♦ Q♦ K♦ A♠ 3♠ 4♠ 5♠ 6♠ 7♠ 8♠ 9♠ 10♠ \
+                J♠ Q♠ K♠;\
+            turn: 1"
+            .parse::<Position>()
+            .unwrap();
+
+        assert_eq!(
+            State::new_from_position(&position).pass_blockers(),
+            CardCollection::new_single(card("7♦"))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use crate::cards::Deck;
+
+        let mut results = Results::new(4);
+        #[allow(unused_mut)]
+        let mut state = State::new(4, Deck::new());
+
+        #[cfg(not(feature = "nostats"))]
+        {
+            let cards = CardCollection::new_from_raw(0x0003_0000_0040_0001);
+            results.update_stats_for(1, &cards, 2);
+            state.add_held(&cards, &cards.card_iterator().next().unwrap());
+            state.add_miss();
+        }
+
+        results.record_win(&state);
+
+        let json = serde_json::to_string(&results).unwrap();
+        let results2 = serde_json::from_str::<Results>(&json).unwrap();
+
+        assert_eq!(results2.games(), results.games());
+        assert_eq!(results2.player_results().len(), 4);
+        assert_eq!(serde_json::to_string(&results2).unwrap(), json);
+
+        // Player results alone
+        let json = serde_json::to_string(&results.player_results()[0]).unwrap();
+        let player_results = serde_json::from_str::<PlayerResults>(&json).unwrap();
+        assert_eq!(serde_json::to_string(&player_results).unwrap(), json);
     }
 }
@@ -0,0 +1,205 @@
+use numformat::NumFormat;
+use rand::{Rng, RngCore};
+
+use crate::cards::Card;
+
+use super::{state::State, Move, Observation, Strategy};
+
+/// A player taking part in a single game, choosing each card from what they can see
+pub trait Seat {
+    /// Chooses the card to play, or None to pass. The view's player always has a card to
+    /// play unless they must pass
+    fn choose(&mut self, view: &Observation, rng: &mut dyn RngCore) -> Option<Card>;
+
+    /// Short description of the player
+    fn name(&self) -> String;
+}
+
+impl Seat for Strategy {
+    /// Chooses randomly between the cards the strategy prefers
+    fn choose(&mut self, view: &Observation, rng: &mut dyn RngCore) -> Option<Card> {
+        let candidates = self.candidates(view);
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        candidates
+            .card_iterator()
+            .nth(rng.gen_range(0..candidates.len()))
+    }
+
+    fn name(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// A single game played by seats
+#[derive(Debug, Clone)]
+pub struct PlayedGame {
+    /// Moves made
+    pub moves: Vec<Move>,
+    /// Winning player
+    pub winner: usize,
+    /// Number of cards each player had left at the end
+    pub cards_left: Vec<usize>,
+}
+
+/// Plays a single game from a state with a seat for each player
+pub fn play_game(
+    mut state: State,
+    seats: &mut [Box<dyn Seat>],
+    rng: &mut dyn RngCore,
+) -> PlayedGame {
+    assert_eq!(
+        seats.len(),
+        state.player_count(),
+        "one seat is needed per player"
+    );
+
+    let mut moves = Vec::new();
+
+    loop {
+        let player = state.cur_player();
+        let legal = state.playable_cards().0;
+
+        let card = if legal.is_empty() {
+            None
+        } else {
+            let card = seats[player].choose(&Observation::new(&state, &moves), rng);
+
+            match card {
+                Some(card) if legal.contains(card.clone()) => Some(card),
+                _ => panic!("{} made an illegal move", seats[player].name()),
+            }
+        };
+
+        match card {
+            Some(card) => {
+                moves.push(Move::Play(player, card.clone()));
+                state.play_card(card);
+
+                if state.cur_player_cards().is_empty() {
+                    return PlayedGame {
+                        moves,
+                        winner: player,
+                        cards_left: state.all_player_cards().iter().map(|h| h.len()).collect(),
+                    };
+                }
+            }
+            None => moves.push(Move::Pass(player)),
+        }
+
+        state.next_player();
+    }
+}
+
+/// Wins from single games played by seats
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    /// Name of each player's seat
+    pub names: Vec<String>,
+    /// Number of games each player won
+    pub wins: Vec<usize>,
+    /// Number of games played
+    pub games: usize,
+}
+
+impl Simulation {
+    /// Returns the fraction of games a player won
+    pub fn win_rate(&self, player: usize) -> f64 {
+        self.wins[player] as f64 / self.games.max(1) as f64
+    }
+
+    /// Prints the wins for each player with 95% confidence intervals of the win rates
+    pub fn print(&self) {
+        println!("Wins:");
+
+        for (player, (name, count)) in self.names.iter().zip(&self.wins).enumerate() {
+            let rate = self.win_rate(player);
+            let margin = 1.96 * (rate * (1.0 - rate) / self.games.max(1) as f64).sqrt();
+
+            println!(
+                "  Player {} ({name}): {} ({:.1}% ± {:.1}%)",
+                player + 1,
+                count.num_format(),
+                rate * 100.0,
+                margin * 100.0
+            );
+        }
+    }
+}
+
+/// Plays a number of single games from a state with a seat for each player
+pub fn simulate(
+    state: State,
+    seats: &mut [Box<dyn Seat>],
+    games: usize,
+    rng: &mut dyn RngCore,
+) -> Simulation {
+    let mut wins = vec![0; seats.len()];
+
+    for _ in 0..games {
+        wins[play_game(state.clone(), seats, rng).winner] += 1;
+    }
+
+    Simulation {
+        names: seats.iter().map(|s| s.name()).collect(),
+        wins,
+        games,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::Deck;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_play_game() {
+        let state = State::new(4, Deck::new());
+
+        let mut seats: Vec<Box<dyn Seat>> = vec![
+            Box::new(Strategy::Preferred),
+            Box::new(Strategy::Dumb),
+            Box::new(Strategy::NoConsequence),
+            Box::new(Strategy::Preferred),
+        ];
+
+        let game = play_game(state.clone(), &mut seats, &mut StdRng::seed_from_u64(1));
+
+        // Every card is played apart from the rest of the losers' hands
+        let played = game
+            .moves
+            .iter()
+            .filter(|m| matches!(m, Move::Play(..)))
+            .count();
+        assert!(played > 13 && played <= 52);
+        assert!(matches!(game.moves.last(), Some(Move::Play(p, _)) if *p == game.winner));
+        assert_eq!(game.cards_left[game.winner], 0);
+        assert_eq!(game.cards_left.iter().sum::<usize>(), 52 - played);
+
+        // Same seed gives the same game
+        let again = play_game(state, &mut seats, &mut StdRng::seed_from_u64(1));
+        assert_eq!(again.moves, game.moves);
+    }
+
+    #[test]
+    fn test_simulate() {
+        let mut seats: Vec<Box<dyn Seat>> =
+            vec![Box::new(Strategy::Preferred), Box::new(Strategy::Dumb)];
+
+        let simulation = simulate(
+            State::new(2, Deck::new()),
+            &mut seats,
+            20,
+            &mut StdRng::seed_from_u64(1),
+        );
+
+        assert_eq!(simulation.games, 20);
+        assert_eq!(simulation.wins.iter().sum::<usize>(), 20);
+        assert_eq!(simulation.names, vec!["Preferred", "Dumb"]);
+        assert_eq!(simulation.win_rate(0), simulation.wins[0] as f64 / 20.0);
+    }
+}
@@ -0,0 +1,86 @@
+/// Deepest point in the game tree at which adaptive splitting gives branches to other threads.
+/// Sub-trees below this are too small to be worth the overhead
+const ADAPTIVE_MAX_DEPTH: usize = 40;
+
+/// Number of tokio tasks per worker thread to keep alive when splitting adaptively
+const TASKS_PER_WORKER: usize = 4;
+
+/// When to split branches of the game tree off to other threads
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Split {
+    /// Split while there are idle threads to take the work
+    #[default]
+    Adaptive,
+    /// Split every branch before this many cards have been played
+    Depth(usize),
+}
+
+impl Split {
+    /// Returns true if branches from a state with this many cards played should be run by
+    /// other threads in the rayon thread pool, given the number of tasks waiting for a thread
+    #[inline]
+    pub(crate) fn rayon(&self, cards_played: usize, queued: usize) -> bool {
+        match self {
+            Split::Adaptive => {
+                // Only split when there are threads with nothing queued for them
+                cards_played < ADAPTIVE_MAX_DEPTH
+                    && rayon::current_num_threads() > 1
+                    && queued < rayon::current_num_threads()
+            }
+            Split::Depth(depth) => cards_played < *depth,
+        }
+    }
+
+    /// Returns true if branches from a state with this many cards played should be spawned
+    /// as tokio tasks
+    #[inline]
+    pub(crate) fn tokio(&self, cards_played: usize) -> bool {
+        match self {
+            Split::Adaptive => {
+                if cards_played >= ADAPTIVE_MAX_DEPTH {
+                    return false;
+                }
+
+                // Keep enough tasks alive for all of the workers to stay busy
+                let metrics = tokio::runtime::Handle::current().metrics();
+
+                metrics.num_alive_tasks() < metrics.num_workers() * TASKS_PER_WORKER
+            }
+            Split::Depth(depth) => cards_played < *depth,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth() {
+        let split = Split::Depth(18);
+
+        assert!(split.rayon(17, 100));
+        assert!(!split.rayon(18, 0));
+    }
+
+    #[test]
+    fn test_adaptive() {
+        let split = Split::Adaptive;
+
+        // Never split with a single thread
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        assert!(!pool.install(|| split.rayon(0, 0)));
+
+        // Split while threads have nothing queued
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        assert!(pool.install(|| split.rayon(0, 1)));
+        assert!(!pool.install(|| split.rayon(0, 2)));
+        assert!(!pool.install(|| split.rayon(ADAPTIVE_MAX_DEPTH, 0)));
+    }
+}
@@ -1,11 +1,14 @@
 use std::cmp::Ordering;
 
 use crate::{
-    cards::{Card, CardCollection, Deck},
-    game::SEVEN_HEARTS,
+    cards::{Card, CardCollection, Deck, Rank, Suit},
+    game::{Position, SEVEN_HEARTS},
 };
 
+use super::ALL_CARDS;
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Game state
 pub struct State {
     /// The current board state
@@ -16,8 +19,20 @@ pub struct State {
     cur_player: usize,
     /// Player cards
     player_cards: Vec<CardCollection>,
+    /// Goes missed by each player before the position the state was created from. Empty if
+    /// nobody had missed a go, so cloning the state doesn't allocate for it
+    #[cfg_attr(feature = "serde", serde(default))]
+    start_passes: Vec<usize>,
     #[cfg(not(feature = "nostats"))]
     misses: usize,
+    /// Number of times each card was playable but not played in this game
+    #[cfg(not(feature = "nostats"))]
+    #[cfg_attr(feature = "serde", serde(with = "super::results::card_counts"))]
+    card_held: [u16; 52],
+    /// Number of passes caused solely by each card not being played in this game
+    #[cfg(not(feature = "nostats"))]
+    #[cfg_attr(feature = "serde", serde(with = "super::results::card_counts"))]
+    card_pass_blocks: [u16; 52],
 }
 
 impl State {
@@ -30,9 +45,14 @@ impl State {
             player_cards: (0..player_count)
                 .map(|_| CardCollection::new())
                 .collect::<Vec<_>>(),
+            start_passes: Vec::new(),
             cur_player: 0,
             #[cfg(not(feature = "nostats"))]
             misses: 0,
+            #[cfg(not(feature = "nostats"))]
+            card_held: [0; 52],
+            #[cfg(not(feature = "nostats"))]
+            card_pass_blocks: [0; 52],
         };
 
         // Deal the cards
@@ -55,7 +75,6 @@ impl State {
     }
 
     #[inline]
-    #[cfg(feature = "trace")]
     /// Returns the current board
     pub fn board(&self) -> &CardCollection {
         &self.board
@@ -85,6 +104,12 @@ impl State {
         &self.player_cards[self.cur_player]
     }
 
+    /// Returns the number of goes a player had missed before the position the state was
+    /// created from
+    pub fn start_passes(&self, player: usize) -> usize {
+        self.start_passes.get(player).copied().unwrap_or(0)
+    }
+
     /// Move to the next player
     #[inline]
     pub fn next_player(&mut self) {
@@ -98,27 +123,103 @@ impl State {
         self.cur_player = new_player;
     }
 
+    /// Creates a game state from a position part way through a game
+    pub fn new_from_position(position: &Position) -> Self {
+        let mut state = Self {
+            board: CardCollection::new(),
+            valid_moves: CardCollection::new_single(SEVEN_HEARTS.clone()),
+            player_cards: position.hands().to_vec(),
+            start_passes: if position.passes().iter().any(|&p| p > 0) {
+                position.passes().to_vec()
+            } else {
+                Vec::new()
+            },
+            cur_player: position.to_move(),
+            #[cfg(not(feature = "nostats"))]
+            misses: position.passes().iter().sum(),
+            #[cfg(not(feature = "nostats"))]
+            card_held: [0; 52],
+            #[cfg(not(feature = "nostats"))]
+            card_pass_blocks: [0; 52],
+        };
+
+        // Lay out the board
+        position
+            .board()
+            .card_iterator()
+            .for_each(|c| state.add_to_board(c));
+
+        state
+    }
+
+    /// Returns a copy of the state with the players holding different cards. If no cards
+    /// have been played the player holding the 7♥ starts
+    pub(crate) fn with_hands(&self, hands: Vec<CardCollection>) -> Self {
+        let cur_player = if self.board.is_empty() {
+            hands
+                .iter()
+                .position(|h| h.contains(SEVEN_HEARTS.clone()))
+                .expect("Player with 7♥ not found")
+        } else {
+            self.cur_player
+        };
+
+        Self {
+            player_cards: hands,
+            cur_player,
+            ..self.clone()
+        }
+    }
+
     /// Play a card
     #[inline]
     pub fn play_card(&mut self, card: Card) {
+        // Remove the card from the player's hand
+        self.player_cards[self.cur_player].remove(card.clone());
+
+        // Add the card to the board
+        self.add_to_board(card);
+    }
+
+    /// Adds a card to the board and updates the valid moves
+    #[inline]
+    fn add_to_board(&mut self, card: Card) {
         if card == SEVEN_HEARTS {
             // Other sevens can now be played
-            self.valid_moves.add(Card::new(&'♣', "7").unwrap());
-            self.valid_moves.add(Card::new(&'♦', "7").unwrap());
-            self.valid_moves.add(Card::new(&'♠', "7").unwrap());
+            Suit::iter().filter(|s| *s != Suit::Hearts).for_each(|s| {
+                self.valid_moves
+                    .add(Card::new_from_suit_rank(s, Rank::Seven))
+            });
         }
 
         // NB! This adds invalid moves when an ace or king is played but the bits set aren't used
         self.valid_moves.add(card.one_lower());
         self.valid_moves.add(card.one_higher());
 
-        // Remove the card from the player's hand
-        self.player_cards[self.cur_player].remove(card.clone());
-
         // Add the card to the board
         self.board.add(card);
     }
 
+    /// Returns the cards which can be played next on a board. These are the valid moves a
+    /// state with that board would have
+    pub fn open_cards(board: &CardCollection) -> CardCollection {
+        if !board.contains(SEVEN_HEARTS.clone()) {
+            return CardCollection::new_single(SEVEN_HEARTS.clone());
+        }
+
+        let mut valid_moves = CardCollection::new();
+
+        // Any seven can be played once the 7♥ has been
+        Suit::iter().for_each(|s| valid_moves.add(Card::new_from_suit_rank(s, Rank::Seven)));
+
+        board.card_iterator().for_each(|c| {
+            valid_moves.add(c.one_lower());
+            valid_moves.add(c.one_higher());
+        });
+
+        CardCollection::new_from_raw(valid_moves.raw() & !board.raw() & ALL_CARDS)
+    }
+
     #[inline]
     /// Returns the number of cards played
     pub fn cards_played(&self) -> usize {
@@ -140,23 +241,23 @@ impl State {
         let mut sequence_cards = CardCollection::new();
 
         playable_cards.card_iterator().for_each(|c| {
-            let rank_elem = c.rank_elem();
-            let suit_elem = c.suit_elem();
+            let rank = c.rank();
+            let suit = c.suit();
 
-            match rank_elem.cmp(&6) {
+            match rank.cmp(&Rank::Seven) {
                 Ordering::Less => {
                     // Less than 7 - if we have the card one lower as well, or it's an Ace, play this one
-                    if rank_elem == 0 || cards.contains(c.one_lower()) {
+                    if rank == Rank::Ace || cards.contains(c.one_lower()) {
                         no_consequence_cards.add(c);
-                    } else if cards.contains_one_to_six_except(suit_elem, &c) {
+                    } else if cards.contains_one_to_six_except(suit, &c) {
                         sequence_cards.add(c);
                     }
                 }
                 Ordering::Greater => {
                     // More than 7 - if we have the card one higher as well, or it's a King, play this one
-                    if rank_elem == 12 || cards.contains(c.one_higher()) {
+                    if rank == Rank::King || cards.contains(c.one_higher()) {
                         no_consequence_cards.add(c);
-                    } else if cards.contains_eight_to_king_except(suit_elem, &c) {
+                    } else if cards.contains_eight_to_king_except(suit, &c) {
                         sequence_cards.add(c);
                     }
                 }
@@ -164,8 +265,8 @@ impl State {
                     // A 7. Play if we have the higher and lower card
                     if cards.contains(c.one_lower()) && cards.contains(c.one_higher()) {
                         no_consequence_cards.add(c);
-                    } else if cards.contains_one_to_six_except(suit_elem, &c)
-                        || cards.contains_eight_to_king_except(suit_elem, &c)
+                    } else if cards.contains_one_to_six_except(suit, &c)
+                        || cards.contains_eight_to_king_except(suit, &c)
                     {
                         sequence_cards.add(c);
                     }
@@ -204,8 +305,23 @@ impl State {
 
     #[cfg(not(feature = "nostats"))]
     #[inline]
-    pub fn add_miss(&mut self) {
+    /// Records the current player passing and the cards responsible
+    pub(crate) fn add_miss(&mut self) {
         self.misses += 1;
+
+        self.pass_blockers()
+            .card_iterator()
+            .for_each(|c| self.card_pass_blocks[c.index()] += 1);
+    }
+
+    #[cfg(not(feature = "nostats"))]
+    #[inline]
+    /// Records the playable cards held back when a card is played
+    pub(crate) fn add_held(&mut self, playable: &CardCollection, played: &Card) {
+        playable
+            .card_iterator()
+            .filter(|c| c != played)
+            .for_each(|c| self.card_held[c.index()] += 1);
     }
 
     #[cfg(not(feature = "nostats"))]
@@ -213,4 +329,112 @@ impl State {
     pub fn get_misses(&self) -> usize {
         self.misses
     }
+
+    #[cfg(not(feature = "nostats"))]
+    #[inline]
+    pub(crate) fn card_held(&self) -> &[u16; 52] {
+        &self.card_held
+    }
+
+    #[cfg(not(feature = "nostats"))]
+    #[inline]
+    pub(crate) fn card_pass_blocks(&self) -> &[u16; 52] {
+        &self.card_pass_blocks
+    }
+
+    #[cfg(not(feature = "nostats"))]
+    /// Returns the cards on the edge of the board which are the only card missing before
+    /// the current player could play one of their own cards
+    pub(crate) fn pass_blockers(&self) -> CardCollection {
+        let cards = &self.player_cards[self.cur_player()];
+
+        // Cards which can be played on the board but haven't been yet
+        let open =
+            CardCollection::new_from_raw(self.valid_moves.raw() & !self.board.raw() & ALL_CARDS);
+
+        let mut blockers = CardCollection::new();
+
+        open.card_iterator().for_each(|c| {
+            let rank = c.rank();
+
+            let blocking = match rank.cmp(&Rank::Seven) {
+                // Less than 7 - blocks the card one lower
+                Ordering::Less => rank != Rank::Ace && cards.contains(c.one_lower()),
+                // More than 7 - blocks the card one higher
+                Ordering::Greater => rank != Rank::King && cards.contains(c.one_higher()),
+                // A 7 - blocks the 6 and the 8
+                Ordering::Equal => cards.contains(c.one_lower()) || cards.contains(c.one_higher()),
+            };
+
+            if blocking {
+                blockers.add(c);
+            }
+        });
+
+        blockers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::tests::endgame_state;
+
+    use super::*;
+
+    #[test]
+    fn test_open_cards() {
+        let board = CardCollection::new();
+        assert_eq!(State::open_cards(&board).to_string(), "7♥");
+
+        let board = "7♥ 6♥ 8♥ 7♣ A♠ 2♠ 3♠ 4♠ 5♠ 6♠ 7♠ 8♠ 9♠ 10♠ J♠ Q♠ K♠"
+            .parse::<CardCollection>()
+            .unwrap();
+        assert_eq!(State::open_cards(&board).to_string(), "5♥ 9♥ 6♣ 8♣ 7♦");
+
+        // Matches the moves a state with the board allows
+        let endgame = endgame_state();
+        let open = State::open_cards(endgame.board());
+        let rest = CardCollection::new_from_raw(ALL_CARDS & !endgame.board().raw());
+
+        let state = endgame.with_hands(vec![rest; endgame.player_count()]);
+        assert_eq!(state.playable_cards().0, open);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut deck = Deck::new();
+        deck.shuffle();
+
+        let mut state = State::new(4, deck);
+        state.play_card(SEVEN_HEARTS);
+        state.next_player();
+
+        let json = serde_json::to_string(&state).unwrap();
+        let state2 = serde_json::from_str::<State>(&json).unwrap();
+
+        assert_eq!(state2.board(), state.board());
+        assert_eq!(state2.cur_player(), state.cur_player());
+        assert_eq!(state2.all_player_cards(), state.all_player_cards());
+        assert_eq!(state2.playable_cards(), state.playable_cards());
+        assert_eq!(serde_json::to_string(&state2).unwrap(), json);
+
+        // Playing a king or an ace leaves moves past the end of the suit
+        let position = "board: A♥ 2♥ 3♥ 4♥ 5♥ 6♥ 7♥ 8♥ 9♥ 10♥ J♥ Q♥ K♥ 7♣ 6♣ 5♣ 4♣ 3♣ 2♣ A♣;\
+            hand: 8♣ 9♣ 10♣ J♣ Q♣ K♣ A♦ 2♦ 3♦ 4♦ 5♦ 6♦ 7♦ 8♦ 9♦ 10♦;\
+            hand: J♦ Q♦ K♦ A♠ 2♠ 3♠ 4♠ 5♠ 6♠ 7♠ 8♠ 9♠ 10♠ J♠ Q♠ K♠;\
+            turn: 1"
+            .parse::<Position>()
+            .unwrap();
+
+        let state = State::new_from_position(&position);
+
+        let json = serde_json::to_string(&state).unwrap();
+        let state2 = serde_json::from_str::<State>(&json).unwrap();
+
+        assert_eq!(state2.board(), state.board());
+        assert_eq!(state2.all_player_cards(), state.all_player_cards());
+        assert_eq!(state2.playable_cards(), state.playable_cards());
+        assert_eq!(serde_json::to_string(&state2).unwrap(), json);
+    }
 }
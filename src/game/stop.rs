@@ -0,0 +1,86 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Handle used to ask a running search to stop early. Clones share the same flag
+#[derive(Debug, Clone, Default)]
+pub struct Stop {
+    flag: Arc<AtomicBool>,
+}
+
+impl Stop {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a handle which asks the search to stop after a time. A timer thread sets
+    /// the flag when the time is up, so checking it stays a single load
+    pub fn after(time: Duration) -> Self {
+        let stop = Self::new();
+
+        if time.is_zero() {
+            stop.stop();
+        } else if Instant::now().checked_add(time).is_some() {
+            // The timer doesn't keep the flag alive once the search has finished
+            let flag = Arc::downgrade(&stop.flag);
+            thread::spawn(move || {
+                thread::sleep(time);
+                if let Some(flag) = flag.upgrade() {
+                    flag.store(true, Ordering::Relaxed);
+                }
+            });
+        }
+
+        stop
+    }
+
+    /// Asks the search to stop as soon as possible
+    pub fn stop(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the search has been asked to stop or its time is up
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop() {
+        let stop = Stop::new();
+        let clone = stop.clone();
+
+        assert!(!stop.is_stopped());
+
+        clone.stop();
+        assert!(stop.is_stopped());
+    }
+
+    #[test]
+    fn test_after() {
+        assert!(Stop::after(Duration::ZERO).is_stopped());
+
+        let stop = Stop::after(Duration::from_secs(3_600));
+        assert!(!stop.is_stopped());
+
+        // Stopping early still works with a time limit
+        stop.clone().stop();
+        assert!(stop.is_stopped());
+
+        // A time too far off to represent never stops the search
+        assert!(!Stop::after(Duration::MAX).is_stopped());
+
+        // The timer stops the search once the time is up
+        let stop = Stop::after(Duration::from_millis(10));
+        thread::sleep(Duration::from_millis(200));
+        assert!(stop.is_stopped());
+    }
+}
@@ -1,11 +1,11 @@
-use clap::ValueEnum;
-
 use crate::cards::CardCollection;
 
 #[cfg(not(feature = "nostats"))]
 use super::Results;
+use super::{Observation, Weights};
 
-#[derive(ValueEnum, Clone, Copy)]
+/// Strategy used to choose which cards to play
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Strategy {
     /// A single no consequence card is the preferred play, then in-sequence cards
     NoConsequence,
@@ -13,15 +13,18 @@ pub enum Strategy {
     Preferred,
     /// All playable cards are considered equal
     Dumb,
+    /// Cards are scored with weights for each of their features and the best are played
+    Weighted(Weights),
 }
 
 impl Strategy {
     #[inline]
-    pub fn choose_cards(
+    pub(crate) fn choose_cards(
         &self,
         no_consequence_cards: CardCollection,
         sequence_cards: CardCollection,
         playable_cards: CardCollection,
+        hand: &CardCollection,
         #[cfg(not(feature = "nostats"))] player: usize,
         #[cfg(not(feature = "nostats"))] results: &mut Results,
     ) -> CardCollection {
@@ -98,6 +101,12 @@ impl Strategy {
                 }
             }
             Strategy::Dumb => playable_cards, // Any playable
+            Strategy::Weighted(weights) => weights.choose(
+                hand,
+                &no_consequence_cards,
+                &sequence_cards,
+                &playable_cards,
+            ),
         };
 
         // Update play stats
@@ -111,12 +120,54 @@ impl Strategy {
         use_card_set
     }
 
+    /// Chooses the cards to consider playing for the player to move from what they can see
+    #[inline]
+    pub(crate) fn choose_cards_for(
+        &self,
+        view: &Observation,
+        #[cfg(not(feature = "nostats"))] results: &mut Results,
+    ) -> CardCollection {
+        let (playable_cards, no_consequence_cards, sequence_cards) = view.playable_cards();
+
+        self.choose_cards(
+            no_consequence_cards,
+            sequence_cards,
+            playable_cards,
+            view.hand(),
+            #[cfg(not(feature = "nostats"))]
+            view.player(),
+            #[cfg(not(feature = "nostats"))]
+            results,
+        )
+    }
+
+    /// Returns the cards the strategy would consider playing for the player to move from
+    /// what they can see. Each one leads to a different game in the exhaustive search
+    pub fn candidates(&self, view: &Observation) -> CardCollection {
+        self.choose_cards_for(
+            view,
+            #[cfg(not(feature = "nostats"))]
+            &mut Results::new(view.player_count() as u8),
+        )
+    }
+
+    /// Returns the weights which make the weighted strategy choose the same cards
+    pub fn weights(&self) -> Weights {
+        match self {
+            Strategy::NoConsequence => Weights::NO_CONSEQUENCE,
+            Strategy::Preferred => Weights::PREFERRED,
+            Strategy::Dumb => Weights::DUMB,
+            Strategy::Weighted(weights) => *weights,
+        }
+    }
+
     #[cfg(not(feature = "nostats"))]
     pub fn max_pref_rank(&self) -> u8 {
         match self {
             Strategy::NoConsequence => 2,
             Strategy::Preferred => 2,
             Strategy::Dumb => 0,
+            Strategy::Weighted(_) => 0,
         }
     }
 
@@ -139,6 +190,21 @@ impl Strategy {
                 0 => "Playable",
                 _ => "Unknown",
             },
+            Strategy::Weighted(_) => match pref_rank {
+                0 => "Best Score",
+                _ => "Unknown",
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strategy::NoConsequence => f.write_str("NoConsequence"),
+            Strategy::Preferred => f.write_str("Preferred"),
+            Strategy::Dumb => f.write_str("Dumb"),
+            Strategy::Weighted(_) => f.write_str("Weighted"),
         }
     }
 }
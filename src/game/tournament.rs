@@ -0,0 +1,543 @@
+use std::io::Write;
+
+use numformat::NumFormat;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::cards::Deck;
+
+use super::{play_game, state::State, Move, PlayedGame, Seat};
+
+/// Number of iterations used to fit the ratings
+const RATING_ITERATIONS: usize = 1_000;
+
+/// Rating of an average entrant
+pub const BASE_RATING: f64 = 1_500.0;
+
+/// Settings for a tournament
+#[derive(Debug, Clone)]
+pub struct TournamentConfig {
+    /// Number of deals to play, each once for every rotation of the entrants
+    pub deals: usize,
+    /// Seed of the first deal. Each deal after it uses the next seed
+    pub seed: u64,
+}
+
+/// A game played in a tournament, with what's needed to replay it
+#[derive(Debug, Clone)]
+pub struct TournamentGame {
+    /// Deal number, from 0
+    pub deal: usize,
+    /// Seed the deal was shuffled and its games played with. A tournament of one deal
+    /// starting from this seed plays the same games
+    pub seed: u64,
+    /// Checksummed hash of the deck dealt
+    pub deck_hash: String,
+    /// Rotation of the entrants around the table
+    pub rotation: usize,
+    /// Entrant in each seat
+    pub seats: Vec<usize>,
+    /// Game played, by seat
+    pub game: PlayedGame,
+    /// Finishing position of each seat, 0 being first
+    pub positions: Vec<usize>,
+}
+
+impl std::fmt::Display for TournamentGame {
+    /// Formats the game as a line of a tournament log, with players and positions from 1
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |values: &[usize], offset: usize| {
+            values
+                .iter()
+                .map(|v| format!("{}", v + offset))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let moves = self
+            .game
+            .moves
+            .iter()
+            .map(|m| match m {
+                Move::Play(_, card) => format!("{card}"),
+                Move::Pass(_) => "-".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        write!(
+            f,
+            "deal={} seed={} deck={} rotation={} entrants={} winner={} positions={} cards_left={} moves={moves}",
+            self.deal + 1,
+            self.seed,
+            self.deck_hash,
+            self.rotation,
+            list(&self.seats, 1),
+            self.game.winner + 1,
+            list(&self.positions, 1),
+            list(&self.game.cards_left, 0),
+        )
+    }
+}
+
+/// Tournament results for one entrant
+#[derive(Debug, Clone)]
+pub struct Standing {
+    /// Name of the entrant
+    pub name: String,
+    /// Number of games played
+    pub games: usize,
+    /// Number of games won
+    pub wins: usize,
+    /// Number of times the entrant finished in each position, first to last
+    pub positions: Vec<usize>,
+    /// Elo-style rating from how often the entrant finished ahead of each other entrant
+    pub rating: f64,
+    /// Standard error of the rating
+    pub rating_error: f64,
+}
+
+impl Standing {
+    /// Returns the fraction of games won
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.games.max(1) as f64
+    }
+
+    /// Returns the average finishing position, 1 being first
+    pub fn average_position(&self) -> f64 {
+        self.positions
+            .iter()
+            .enumerate()
+            .map(|(p, count)| (p + 1) * count)
+            .sum::<usize>() as f64
+            / self.games.max(1) as f64
+    }
+}
+
+/// Results of a tournament
+#[derive(Debug, Clone)]
+pub struct TournamentResults {
+    /// Standing of each entrant, in entry order
+    pub standings: Vec<Standing>,
+    /// Points each entrant scored against each other, one for finishing ahead and a half
+    /// for finishing level
+    pub head_to_head: Vec<Vec<f64>>,
+    /// Number of games played
+    pub games: usize,
+}
+
+impl TournamentResults {
+    /// Returns the entrants from the highest rated to the lowest
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut ranking = (0..self.standings.len()).collect::<Vec<_>>();
+
+        ranking.sort_by(|a, b| {
+            self.standings[*b]
+                .rating
+                .total_cmp(&self.standings[*a].rating)
+        });
+
+        ranking
+    }
+
+    /// Returns the fraction of points an entrant scored against another
+    pub fn score(&self, entrant: usize, opponent: usize) -> f64 {
+        self.head_to_head[entrant][opponent] / self.games.max(1) as f64
+    }
+
+    /// Prints the ranking with 95% confidence intervals and the head-to-head results
+    pub fn print(&self) {
+        let names = self
+            .standings
+            .iter()
+            .enumerate()
+            .map(|(e, s)| format!("{} {}", e + 1, s.name))
+            .collect::<Vec<_>>();
+
+        let name_len = names.iter().map(|n| n.len()).max().unwrap_or(0);
+        let players = self.standings.len();
+        let games = self.games.max(1) as f64;
+
+        println!("Games played: {}", self.games.num_format());
+        println!("Ranking:");
+
+        print!(
+            "  {:>4}  {:<name_len$}  {:>12}  {:>20}  {:>8}",
+            "Rank", "Entrant", "Rating", "Wins", "Avg Pos"
+        );
+        (1..=players).for_each(|p| print!(" {:>7}", ordinal(p)));
+        println!();
+
+        for (rank, entrant) in self.ranking().into_iter().enumerate() {
+            let standing = &self.standings[entrant];
+            let rate = standing.win_rate();
+
+            // 95% confidence intervals
+            let rating = format!(
+                "{:.0} ± {:.0}",
+                standing.rating,
+                1.96 * standing.rating_error
+            );
+            let wins = format!(
+                "{:.1}% ± {:.1}%",
+                rate * 100.0,
+                1.96 * (rate * (1.0 - rate) / games).sqrt() * 100.0
+            );
+
+            print!(
+                "  {:>4}  {:<name_len$}  {rating:>12}  {wins:>20}  {:>8.2}",
+                rank + 1,
+                names[entrant],
+                standing.average_position()
+            );
+            standing
+                .positions
+                .iter()
+                .for_each(|p| print!(" {:>7}", p.num_format()));
+            println!();
+        }
+
+        println!(
+            "Head to head (share of games finishing ahead of each opponent, ties count half):"
+        );
+
+        print!("  {:<name_len$}", "");
+        (1..=players).for_each(|e| print!(" {e:>6}"));
+        println!();
+
+        for (entrant, name) in names.iter().enumerate() {
+            print!("  {name:<name_len$}");
+
+            for opponent in 0..players {
+                if opponent == entrant {
+                    print!(" {:>6}", "-");
+                } else {
+                    print!(
+                        " {:>6}",
+                        format!("{:.1}%", self.score(entrant, opponent) * 100.0)
+                    );
+                }
+            }
+
+            println!();
+        }
+    }
+}
+
+/// Formats a finishing position (1st, 2nd, ...)
+fn ordinal(position: usize) -> String {
+    let suffix = match (position % 10, position % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{position}{suffix}")
+}
+
+/// Plays a tournament between entrants, one for each player. Every deal is played once
+/// for each rotation of the entrants around the table so each entrant plays every hand.
+/// Each game played is passed to `record` as it finishes
+pub fn tournament(
+    entrants: &mut [Box<dyn Seat>],
+    config: &TournamentConfig,
+    mut record: impl FnMut(&TournamentGame),
+) -> TournamentResults {
+    let players = entrants.len();
+
+    assert!(players >= 2, "a tournament needs at least two entrants");
+
+    let mut standings = entrants
+        .iter()
+        .map(|e| Standing {
+            name: e.name(),
+            games: 0,
+            wins: 0,
+            positions: vec![0; players],
+            rating: BASE_RATING,
+            rating_error: 0.0,
+        })
+        .collect::<Vec<_>>();
+
+    let mut head_to_head = vec![vec![0.0; players]; players];
+    let mut games = 0;
+
+    for deal in 0..config.deals {
+        let seed = config.seed.wrapping_add(deal as u64);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut deck = Deck::new();
+        deck.shuffle_with(&mut rng);
+
+        let deck_hash = deck.hash_string_checksummed();
+        let state = State::new(players as u8, deck);
+
+        for rotation in 0..players {
+            // Seat each entrant one place further round the table
+            entrants.rotate_left(rotation);
+            let game = play_game(state.clone(), entrants, &mut rng);
+            entrants.rotate_right(rotation);
+
+            let seats = (0..players)
+                .map(|seat| (seat + rotation) % players)
+                .collect::<Vec<_>>();
+
+            let positions = finishing_positions(&game.cards_left);
+
+            for (seat, entrant) in seats.iter().enumerate() {
+                let standing = &mut standings[*entrant];
+
+                standing.games += 1;
+                standing.positions[positions[seat]] += 1;
+
+                if seat == game.winner {
+                    standing.wins += 1;
+                }
+
+                for (other, opponent) in seats.iter().enumerate() {
+                    if other != seat {
+                        head_to_head[*entrant][*opponent] +=
+                            match positions[seat].cmp(&positions[other]) {
+                                std::cmp::Ordering::Less => 1.0,
+                                std::cmp::Ordering::Equal => 0.5,
+                                std::cmp::Ordering::Greater => 0.0,
+                            };
+                    }
+                }
+            }
+
+            games += 1;
+
+            record(&TournamentGame {
+                deal,
+                seed,
+                deck_hash: deck_hash.clone(),
+                rotation,
+                seats,
+                game,
+                positions,
+            });
+        }
+    }
+
+    for (standing, (rating, error)) in standings.iter_mut().zip(ratings(&head_to_head, games)) {
+        standing.rating = rating;
+        standing.rating_error = error;
+    }
+
+    TournamentResults {
+        standings,
+        head_to_head,
+        games,
+    }
+}
+
+/// Plays a tournament as [`tournament`] does, writing a line for each game to a log. Once
+/// a write fails no more lines are written and the error is returned after the tournament
+pub fn tournament_logged(
+    entrants: &mut [Box<dyn Seat>],
+    config: &TournamentConfig,
+    log: &mut impl Write,
+) -> std::io::Result<TournamentResults> {
+    let mut written = Ok(());
+
+    let results = tournament(entrants, config, |game| {
+        if written.is_ok() {
+            written = writeln!(log, "{game}");
+        }
+    });
+
+    written?;
+    log.flush()?;
+
+    Ok(results)
+}
+
+/// Works out each player's finishing position from the cards they had left, players with
+/// the same number of cards sharing a position
+fn finishing_positions(cards_left: &[usize]) -> Vec<usize> {
+    cards_left
+        .iter()
+        .map(|left| cards_left.iter().filter(|other| *other < left).count())
+        .collect()
+}
+
+/// Fits a Bradley-Terry model to the head-to-head points and returns each entrant's rating
+/// on the Elo scale with its standard error. Every pair of entrants is given one drawn game
+/// so an entrant who never finished ahead still gets a finite rating
+fn ratings(head_to_head: &[Vec<f64>], games: usize) -> Vec<(f64, f64)> {
+    let entrants = head_to_head.len();
+    let meetings = games as f64 + 1.0;
+
+    let points = (0..entrants)
+        .map(|e| {
+            (0..entrants)
+                .filter(|o| *o != e)
+                .map(|o| head_to_head[e][o] + 0.5)
+                .sum::<f64>()
+        })
+        .collect::<Vec<_>>();
+
+    // Strength of each entrant, fitted by minorisation-maximisation
+    let mut strength = vec![1.0; entrants];
+
+    for _ in 0..RATING_ITERATIONS {
+        let mut next = (0..entrants)
+            .map(|e| {
+                let expected = (0..entrants)
+                    .filter(|o| *o != e)
+                    .map(|o| meetings / (strength[e] + strength[o]))
+                    .sum::<f64>();
+
+                points[e] / expected
+            })
+            .collect::<Vec<_>>();
+
+        // Keep the geometric mean at 1 so the average rating is the base rating
+        let mean = (next.iter().map(|s| s.ln()).sum::<f64>() / entrants as f64).exp();
+        next.iter_mut().for_each(|s| *s /= mean);
+
+        strength = next;
+    }
+
+    let scale = 400.0 / 10f64.ln();
+
+    (0..entrants)
+        .map(|e| {
+            let information = (0..entrants)
+                .filter(|o| *o != e)
+                .map(|o| {
+                    let p = strength[e] / (strength[e] + strength[o]);
+                    meetings * p * (1.0 - p)
+                })
+                .sum::<f64>();
+
+            (
+                BASE_RATING + scale * strength[e].ln(),
+                scale / information.sqrt(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Strategy;
+
+    #[test]
+    fn test_finishing_positions() {
+        assert_eq!(finishing_positions(&[3, 0, 5, 3]), vec![1, 0, 3, 1]);
+    }
+
+    #[test]
+    fn test_ratings() {
+        // Entrant 1 finishes ahead of entrant 2 three times in four
+        let ratings = ratings(&[vec![0.0, 300.0], vec![100.0, 0.0]], 400);
+
+        // Elo difference for an expected score of 3/4 is about 191
+        assert!((ratings[0].0 - ratings[1].0 - 191.0).abs() < 2.0);
+        assert!((ratings[0].0 + ratings[1].0 - 2.0 * BASE_RATING).abs() < 1e-6);
+        assert!(ratings[0].1 > 0.0 && ratings[0].1 < 50.0);
+    }
+
+    #[test]
+    fn test_tournament() {
+        let mut entrants: Vec<Box<dyn Seat>> = vec![
+            Box::new(Strategy::Preferred),
+            Box::new(Strategy::Dumb),
+            Box::new(Strategy::Preferred),
+        ];
+
+        let config = TournamentConfig { deals: 20, seed: 7 };
+
+        let mut games = Vec::new();
+        let results = tournament(&mut entrants, &config, |g| games.push(g.clone()));
+
+        assert_eq!(results.games, 60);
+        assert_eq!(games.len(), 60);
+
+        for standing in &results.standings {
+            assert_eq!(standing.games, 60);
+            assert_eq!(standing.positions.iter().sum::<usize>(), 60);
+            assert_eq!(standing.positions[0], standing.wins);
+        }
+
+        // One winner a game
+        assert_eq!(results.standings.iter().map(|s| s.wins).sum::<usize>(), 60);
+
+        // Points between each pair add up to the games played
+        assert_eq!(results.score(0, 1) + results.score(1, 0), 1.0);
+
+        // Each entrant plays each seat of each deal
+        for deal in games.chunks(3) {
+            assert!(deal.iter().all(|g| g.deck_hash == deal[0].deck_hash));
+            assert_eq!(deal[1].seats, vec![1, 2, 0]);
+
+            for game in deal {
+                assert_eq!(game.positions[game.game.winner], 0);
+            }
+        }
+
+        // A deal played again from its seed gives the same games
+        let replay = TournamentConfig {
+            deals: 1,
+            seed: games[9].seed,
+        };
+
+        let mut again = Vec::new();
+        tournament(&mut entrants, &replay, |g| again.push(g.clone()));
+
+        for (a, b) in again.iter().zip(&games[9..12]) {
+            assert_eq!(a.deck_hash, b.deck_hash);
+            assert_eq!(a.game.moves, b.game.moves);
+        }
+
+        // Names and ranking
+        assert_eq!(results.standings[1].name, "Dumb");
+
+        let mut ranking = results.ranking();
+        ranking.sort();
+        assert_eq!(ranking, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_log() {
+        let mut entrants: Vec<Box<dyn Seat>> =
+            vec![Box::new(Strategy::Preferred), Box::new(Strategy::Dumb)];
+
+        let config = TournamentConfig { deals: 2, seed: 7 };
+
+        let mut games = Vec::new();
+        tournament(&mut entrants, &config, |g| games.push(g.clone()));
+
+        let mut log = Vec::new();
+        let results = tournament_logged(&mut entrants, &config, &mut log).unwrap();
+        assert_eq!(results.games, 4);
+
+        // A line for each game with players and positions counted from 1
+        let log = String::from_utf8(log).unwrap();
+        let lines = log.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with(&format!(
+            "deal=1 seed=7 deck={} rotation=1 entrants=2,1 winner={} ",
+            games[1].deck_hash,
+            games[1].game.winner + 1
+        )));
+        assert!(lines.iter().zip(&games).all(|(l, g)| *l == g.to_string()));
+    }
+
+    #[test]
+    fn test_ordinal() {
+        let ordinals = [1, 2, 3, 4, 11, 12, 13, 21, 22, 101, 111]
+            .map(ordinal)
+            .join(" ");
+
+        assert_eq!(
+            ordinals,
+            "1st 2nd 3rd 4th 11th 12th 13th 21st 22nd 101st 111th"
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use crate::cards::{Card, CardCollection, Rank, Suit};
+
+use super::{
+    key_value::{parse_lines, parse_number},
+    KeyValueError, SEVEN_HEARTS,
+};
+
+/// Scores within this of the best score are treated as equal
+const SCORE_TOLERANCE: f64 = 1e-9;
+
+/// Weights given to each feature of a playable card by the weighted strategy. A card's
+/// score is the sum of each feature multiplied by its weight, and the cards with the best
+/// score are played
+///
+/// Weights are written as `key: value` lines (see [`KeyValueError`]), with any weights not
+/// given being zero:
+///
+/// ```text
+/// no_consequence: 2
+/// in_sequence: 1
+/// unlocks_own: 0.5
+/// unlocks_others: -0.5
+/// suit_length: 0
+/// end_distance: 0.1
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Weights {
+    /// Card can be played without giving anyone else a card to play
+    pub no_consequence: f64,
+    /// Card is on the way to another card the player holds in the suit
+    pub in_sequence: f64,
+    /// Number of the player's own cards the card lets them play next
+    pub unlocks_own: f64,
+    /// Number of cards held by others the card lets them play next
+    pub unlocks_others: f64,
+    /// Number of other cards the player holds in the card's suit
+    pub suit_length: f64,
+    /// Number of cards between the card and the end of its suit (A or K)
+    pub end_distance: f64,
+}
+
+impl Weights {
+    /// Weights choosing like the no consequence strategy
+    pub const NO_CONSEQUENCE: Weights = Weights {
+        no_consequence: 2.0,
+        in_sequence: 1.0,
+        unlocks_own: 0.0,
+        unlocks_others: 0.0,
+        suit_length: 0.0,
+        end_distance: 0.0,
+    };
+
+    /// Weights choosing like the preferred strategy
+    pub const PREFERRED: Weights = Weights {
+        no_consequence: 1.0,
+        in_sequence: 1.0,
+        unlocks_own: 0.0,
+        unlocks_others: 0.0,
+        suit_length: 0.0,
+        end_distance: 0.0,
+    };
+
+    /// Weights choosing like the dumb strategy
+    pub const DUMB: Weights = Weights {
+        no_consequence: 0.0,
+        in_sequence: 0.0,
+        unlocks_own: 0.0,
+        unlocks_others: 0.0,
+        suit_length: 0.0,
+        end_distance: 0.0,
+    };
+
+    /// Scores a card the player holding the hand could play
+    pub fn score(
+        &self,
+        card: &Card,
+        hand: &CardCollection,
+        no_consequence_cards: &CardCollection,
+        sequence_cards: &CardCollection,
+    ) -> f64 {
+        let unlocked = unlocked_by(card);
+        let own = unlocked.raw() & hand.raw();
+
+        let suit_length = hand
+            .card_iterator()
+            .filter(|c| c.suit() == card.suit() && c != card)
+            .count();
+
+        let end_distance = match card.rank().cmp(&Rank::Seven) {
+            std::cmp::Ordering::Less => card.rank().elem() - Rank::Ace.elem(),
+            std::cmp::Ordering::Greater => Rank::King.elem() - card.rank().elem(),
+            std::cmp::Ordering::Equal => Rank::Seven.elem() - Rank::Ace.elem(),
+        };
+
+        let flag = |cards: &CardCollection| {
+            if cards.contains(card.clone()) {
+                1.0
+            } else {
+                0.0
+            }
+        };
+
+        self.no_consequence * flag(no_consequence_cards)
+            + self.in_sequence * flag(sequence_cards)
+            + self.unlocks_own * own.count_ones() as f64
+            + self.unlocks_others * (unlocked.len() as u32 - own.count_ones()) as f64
+            + self.suit_length * suit_length as f64
+            + self.end_distance * end_distance as f64
+    }
+
+    /// Chooses the playable cards with the best score. When no consequence cards are
+    /// favoured only the first of them is kept, as they can be played in any order without
+    /// changing what anyone else can play
+    pub fn choose(
+        &self,
+        hand: &CardCollection,
+        no_consequence_cards: &CardCollection,
+        sequence_cards: &CardCollection,
+        playable_cards: &CardCollection,
+    ) -> CardCollection {
+        let scores = playable_cards
+            .card_iterator()
+            .map(|c| {
+                let score = self.score(&c, hand, no_consequence_cards, sequence_cards);
+                (c, score)
+            })
+            .collect::<Vec<_>>();
+
+        let best = scores
+            .iter()
+            .map(|(_, score)| *score)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let mut chosen = scores
+            .into_iter()
+            .filter(|(_, score)| *score >= best - SCORE_TOLERANCE)
+            .fold(CardCollection::new(), |mut acc, (c, _)| {
+                acc.add(c);
+                acc
+            });
+
+        if self.no_consequence > 0.0 {
+            let no_consequence =
+                CardCollection::new_from_raw(chosen.raw() & no_consequence_cards.raw());
+
+            if no_consequence.len() > 1 {
+                chosen = CardCollection::new_from_raw(
+                    (chosen.raw() & !no_consequence.raw()) | no_consequence.first(),
+                );
+            }
+        }
+
+        chosen
+    }
+}
+
+/// Returns the cards which can be played once a card has been played
+fn unlocked_by(card: &Card) -> CardCollection {
+    let mut cards = CardCollection::new();
+
+    if *card == SEVEN_HEARTS {
+        Suit::iter()
+            .filter(|s| *s != Suit::Hearts)
+            .for_each(|s| cards.add(Card::new_from_suit_rank(s, Rank::Seven)));
+    }
+
+    let rank = card.rank();
+
+    if rank <= Rank::Seven {
+        if let Some(lower) = rank.lower() {
+            cards.add(Card::new_from_suit_rank(card.suit(), lower));
+        }
+    }
+
+    if rank >= Rank::Seven {
+        if let Some(higher) = rank.higher() {
+            cards.add(Card::new_from_suit_rank(card.suit(), higher));
+        }
+    }
+
+    cards
+}
+
+impl std::fmt::Display for Weights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "no_consequence: {}", self.no_consequence)?;
+        writeln!(f, "in_sequence: {}", self.in_sequence)?;
+        writeln!(f, "unlocks_own: {}", self.unlocks_own)?;
+        writeln!(f, "unlocks_others: {}", self.unlocks_others)?;
+        writeln!(f, "suit_length: {}", self.suit_length)?;
+        writeln!(f, "end_distance: {}", self.end_distance)
+    }
+}
+
+impl std::str::FromStr for Weights {
+    type Err = WeightsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Weights::default();
+
+        parse_lines(s, |key, value| {
+            let weight = match key {
+                "no_consequence" => &mut weights.no_consequence,
+                "in_sequence" => &mut weights.in_sequence,
+                "unlocks_own" => &mut weights.unlocks_own,
+                "unlocks_others" => &mut weights.unlocks_others,
+                "suit_length" => &mut weights.suit_length,
+                "end_distance" => &mut weights.end_distance,
+                key => return Err(KeyValueError::UnknownKey(key.to_string())),
+            };
+
+            *weight = parse_number::<f64>(value)
+                .ok()
+                .filter(|n| n.is_finite())
+                .ok_or_else(|| KeyValueError::InvalidNumber(value.to_string()))?;
+
+            Ok(())
+        })?;
+
+        Ok(weights)
+    }
+}
+
+/// Errors found when parsing weights
+pub type WeightsError = KeyValueError;
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::cards::Deck;
+    use crate::game::{Observation, State, Strategy};
+
+    fn cards(s: &str) -> CardCollection {
+        s.parse().unwrap()
+    }
+
+    fn card(s: &str) -> Card {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let weights = "# Prefer cards far from the ends\nno_consequence: 2; in_sequence: 1\nend_distance: -0.5"
+            .parse::<Weights>()
+            .unwrap();
+
+        assert_eq!(
+            weights,
+            Weights {
+                no_consequence: 2.0,
+                in_sequence: 1.0,
+                end_distance: -0.5,
+                ..Weights::default()
+            }
+        );
+
+        // Round trip
+        assert_eq!(format!("{weights}").parse::<Weights>().unwrap(), weights);
+
+        assert_eq!(
+            "suit_length 1".parse::<Weights>(),
+            Err(WeightsError::Syntax("suit_length 1".to_string()))
+        );
+        assert_eq!(
+            "colour: 1".parse::<Weights>(),
+            Err(WeightsError::UnknownKey("colour".to_string()))
+        );
+        assert_eq!(
+            "suit_length: many".parse::<Weights>(),
+            Err(WeightsError::InvalidNumber("many".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_score() {
+        let hand = cards("7♥ 6♥ 5♥ 9♣ 7♠");
+        let none = CardCollection::new();
+
+        let features = |weights: Weights, c: &str| weights.score(&card(c), &hand, &none, &none);
+
+        let unlocks_own = Weights {
+            unlocks_own: 1.0,
+            ..Weights::default()
+        };
+        let unlocks_others = Weights {
+            unlocks_others: 1.0,
+            ..Weights::default()
+        };
+        let suit_length = Weights {
+            suit_length: 1.0,
+            ..Weights::default()
+        };
+        let end_distance = Weights {
+            end_distance: 1.0,
+            ..Weights::default()
+        };
+
+        // The 7♥ opens the other sevens, the 6♥ and the 8♥
+        assert_eq!(features(unlocks_own, "7♥"), 2.0);
+        assert_eq!(features(unlocks_others, "7♥"), 3.0);
+        assert_eq!(features(unlocks_own, "6♥"), 1.0);
+        assert_eq!(features(unlocks_others, "9♣"), 1.0);
+        assert_eq!(features(unlocks_others, "7♠"), 2.0);
+
+        assert_eq!(features(suit_length, "6♥"), 2.0);
+        assert_eq!(features(suit_length, "9♣"), 0.0);
+
+        assert_eq!(features(end_distance, "5♥"), 4.0);
+        assert_eq!(features(end_distance, "9♣"), 4.0);
+        assert_eq!(features(end_distance, "7♠"), 6.0);
+    }
+
+    #[test]
+    fn test_builtin_weights() {
+        // Weights for the built-in strategies choose the same cards as them
+        let mut rng = StdRng::seed_from_u64(50);
+
+        for _ in 0..20 {
+            let mut deck = Deck::new();
+            deck.shuffle_with(&mut rng);
+
+            let mut state = State::new(4, deck);
+
+            loop {
+                let view = Observation::new(&state, &[]);
+
+                for strategy in [Strategy::NoConsequence, Strategy::Preferred, Strategy::Dumb] {
+                    assert_eq!(
+                        Strategy::Weighted(strategy.weights()).candidates(&view),
+                        strategy.candidates(&view),
+                        "{strategy} differs with hand {}",
+                        state.cur_player_cards()
+                    );
+                }
+
+                if let Some(card) = Strategy::Dumb.candidates(&view).card_iterator().next() {
+                    state.play_card(card);
+
+                    if state.cur_player_cards().is_empty() {
+                        break;
+                    }
+                }
+
+                state.next_player();
+            }
+        }
+    }
+}
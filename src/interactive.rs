@@ -0,0 +1,162 @@
+use std::io::{stdin, stdout, Write};
+use std::process::exit;
+
+use numformat::NumFormat;
+
+use sevens::{
+    cards::{BoardLayout, Card, CardIterPrint, DisplaySymbols, Symbols},
+    game::{hint, HintLimits, Move, Observation, Seat, State, Strategy},
+};
+
+/// Plays a single game with some seats taken by people at the terminal (None) and the rest
+/// by bots. People can ask for hints, which assume everyone goes on to play with the
+/// strategy
+pub fn play_interactive(
+    mut state: State,
+    seats: &mut [Option<Box<dyn Seat>>],
+    strategy: Strategy,
+    hint_limits: &HintLimits,
+    symbols: Symbols,
+) {
+    let mut rng = rand::thread_rng();
+    let mut passes = vec![0; state.player_count()];
+    let mut history = Vec::new();
+
+    let winner = loop {
+        let player = state.cur_player();
+
+        let card = if let Some(bot) = &mut seats[player] {
+            if state.playable_cards().0.is_empty() {
+                None
+            } else {
+                bot.choose(&Observation::new(&state, &history), &mut rng)
+            }
+        } else {
+            println!();
+            println!("Board:");
+            BoardLayout::new(state.board()).symbols(symbols).print("  ");
+            state
+                .cur_player_cards()
+                .card_iterator()
+                .print(&format!("Player {} hand:", player + 1), symbols);
+
+            human_move(&state, strategy, hint_limits, symbols)
+        };
+
+        match card {
+            Some(card) => {
+                println!("Player {} plays {}", player + 1, card.display(symbols));
+                history.push(Move::Play(player, card.clone()));
+                state.play_card(card);
+
+                if state.cur_player_cards().is_empty() {
+                    break player;
+                }
+            }
+            None => {
+                println!("Player {} passes", player + 1);
+                history.push(Move::Pass(player));
+                passes[player] += 1;
+            }
+        }
+
+        state.next_player();
+    };
+
+    println!();
+    println!("Board:");
+    BoardLayout::new(state.board()).symbols(symbols).print("  ");
+    println!("Player {} wins!", winner + 1);
+
+    println!("Passes:");
+    for (player, count) in passes.iter().enumerate() {
+        let seat = match &seats[player] {
+            Some(bot) => bot.name(),
+            None => "human".to_string(),
+        };
+
+        println!("  Player {} ({seat}): {count}", player + 1);
+    }
+}
+
+/// Asks the current player for the card to play, returning None if they have to pass
+fn human_move(
+    state: &State,
+    strategy: Strategy,
+    hint_limits: &HintLimits,
+    symbols: Symbols,
+) -> Option<Card> {
+    let legal = state.playable_cards().0;
+    let legal_str = legal.display(symbols);
+
+    if legal.is_empty() {
+        prompt("You can't play any cards. Press Enter to pass: ");
+        return None;
+    }
+
+    loop {
+        let input = prompt(&format!("Play a card ({legal_str}) or 'hint': "));
+
+        if input.eq_ignore_ascii_case("hint") || input.eq_ignore_ascii_case("h") {
+            print_hints(state, strategy, hint_limits, symbols);
+            continue;
+        }
+
+        match input.parse::<Card>() {
+            Ok(card) if legal.contains(card.clone()) => return Some(card),
+            Ok(card) => println!(
+                "{} can't be played. Legal cards: {legal_str}",
+                card.display(symbols)
+            ),
+            Err(e) => println!("'{input}' is not a card ({e}). Legal cards: {legal_str}"),
+        }
+    }
+}
+
+/// Prints the win rate for each card the current player can play, best first
+pub fn print_hints(state: &State, strategy: Strategy, limits: &HintLimits, symbols: Symbols) {
+    println!(
+        "Hints for player {} (others playing {strategy}):",
+        state.cur_player() + 1
+    );
+
+    let hints = hint(state, strategy, limits);
+
+    if hints.is_empty() {
+        println!("  No cards can be played");
+    }
+
+    for h in hints {
+        let method = match h.games {
+            Some(games) => format!("{} games", games.num_format()),
+            None => format!("estimated from {} random games", limits.probes.num_format()),
+        };
+
+        println!(
+            "  {:>3} {:6.2}% ({method})",
+            h.card.display(symbols).to_string(),
+            h.win_rate * 100.0
+        );
+    }
+}
+
+/// Prints a prompt and reads a line of input. Exits at the end of input or on "quit"
+fn prompt(text: &str) -> String {
+    print!("{text}");
+    stdout().flush().unwrap();
+
+    let mut input = String::new();
+
+    if stdin().read_line(&mut input).unwrap_or(0) == 0 {
+        println!();
+        exit(0);
+    }
+
+    let input = input.trim();
+
+    if input.eq_ignore_ascii_case("quit") || input.eq_ignore_ascii_case("q") {
+        exit(0);
+    }
+
+    input.to_string()
+}
@@ -0,0 +1,7 @@
+//! Sevens card game simulator.
+//!
+//! Plays every possible game of sevens from a deal for a given strategy and collects
+//! the results.
+
+pub mod cards;
+pub mod game;
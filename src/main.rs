@@ -1,20 +1,33 @@
+use std::fs;
+use std::io::BufWriter;
+use std::path::Path;
 use std::process::exit;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use numformat::NumFormat;
 use simple_process_stats::ProcessStats;
 
-mod cards;
-mod game;
+mod interactive;
+#[cfg(feature = "tui")]
+mod tui;
 
-use crate::{
-    cards::{CardIterPrint, Deck},
-    game::{play, State, Strategy},
+use crate::interactive::{play_interactive, print_hints};
+use sevens::{
+    cards::{BoardLayout, CardCollection, CardIterPrint, DealId, Deck, Symbols},
+    game::{
+        estimate, evaluate_hand, export_dot, find, games_per_second, play, play_dfs, replay,
+        simulate, tournament, tournament_logged, DotLimits, EvaluateLimits, GameFilter, GameLine,
+        GamePath, HintLimits, Ismcts, IsmctsLimits, Position, Seat, Split, State, Stop, Strategy,
+        TournamentConfig, Weights,
+    },
 };
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
-pub struct Args {
+#[command(group(clap::ArgGroup::new("seeded").args(["tournament", "evaluate", "dot_probes"])))]
+struct Args {
     /// Number of players
     #[arg(short, long = "players", value_parser = clap::value_parser!(u8).range(2..=52), default_value_t = 6)]
     player_count: u8,
@@ -23,67 +36,558 @@ pub struct Args {
     #[arg(short, long)]
     no_shuffle: bool,
 
-    /// Deck hash
+    /// Deck hash, or a list of all 52 cards separated by spaces or commas
     #[arg(short, long)]
     deck_hash: Option<String>,
 
+    /// Deal ID identifying the hands dealt to each player, for the number of players
+    #[arg(long, conflicts_with_all = ["deck_hash", "no_shuffle"])]
+    deal_id: Option<String>,
+
+    /// Cards in a player's hand (eg. "A♥ 5♥ 9♥" or AH,5H,9H). Give once for each player
+    #[arg(long = "hand", conflicts_with_all = ["deck_hash", "no_shuffle", "deal_id"])]
+    hands: Vec<String>,
+
+    /// Start from a position part way through a game, given as a file name or a string
+    #[arg(long, conflicts_with_all = ["deck_hash", "no_shuffle", "hands", "deal_id"])]
+    position: Option<String>,
+
     /// Strategy
-    #[arg(short, long, value_enum, default_value_t = Strategy::Preferred)]
-    strategy: Strategy,
+    #[arg(short, long, value_enum, default_value_t = StrategyArg::Preferred)]
+    strategy: StrategyArg,
+
+    /// Weights for the weighted strategy, given as a file name or as a string containing
+    /// `key: value` weights. Give once for each weighted tournament entrant, the first also
+    /// being used by `--strategy weighted`
+    #[arg(long)]
+    weights: Vec<String>,
+
+    /// Search engine used to play the games
+    #[arg(short, long, value_enum, default_value_t = Engine::Dfs)]
+    engine: Engine,
+
+    /// Split branches between threads before this many cards have been played instead of
+    /// while there are idle threads
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=52))]
+    split_depth: Option<u8>,
+
+    /// Stop playing after this many seconds and print the results so far
+    #[arg(long)]
+    time_limit: Option<u64>,
+
+    /// Maximum number of threads to play games with (default is one per CPU)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    threads: Option<u16>,
+
+    /// Display suits as ASCII letters (H, C, D, S) instead of symbols
+    #[arg(long)]
+    ascii: bool,
+
+    /// Replay a single game given by its branch choices (eg. 0.2.1.0)
+    #[arg(long, conflicts_with = "find_winner")]
+    replay: Option<GamePath>,
+
+    /// Replay the first game won by this player
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=52))]
+    find_winner: Option<u8>,
+
+    /// Maximum number of missed goes for the winner of the game to replay
+    #[arg(long, requires = "find_winner")]
+    find_max_misses: Option<usize>,
+
+    /// Write the replayed game to a file
+    #[arg(long)]
+    export: Option<String>,
+
+    /// Write the game tree to a Graphviz DOT file
+    #[arg(long)]
+    dot: Option<String>,
+
+    /// Maximum number of moves in the game tree DOT file
+    #[arg(long, default_value_t = 8, requires = "dot")]
+    dot_depth: usize,
+
+    /// Maximum number of nodes in the game tree DOT file
+    #[arg(long, default_value_t = 500, requires = "dot")]
+    dot_nodes: usize,
+
+    /// Estimate the wins in each sub-tree cut off from the game tree DOT file from this many
+    /// random games, rather than playing all of its games
+    #[arg(long, requires = "dot")]
+    dot_probes: Option<usize>,
+
+    /// Play a game interactively with a person in this seat (1 is the first player). Give
+    /// more than once for more people. Other seats are played by the strategy
+    #[arg(long = "human", value_parser = clap::value_parser!(u8).range(1..=52),
+          conflicts_with_all = ["replay", "find_winner", "dot", "estimate", "hint"])]
+    humans: Vec<u8>,
+
+    /// Play this seat with the ISMCTS player instead of the strategy in interactive games
+    /// and simulations (1 is the first player), other than a seat played by a person. Give
+    /// more than once for more seats
+    #[arg(long = "ismcts", value_parser = clap::value_parser!(u8).range(1..=52))]
+    ismcts_seats: Vec<u8>,
+
+    /// Number of games the ISMCTS player plays out for each move
+    #[arg(long, default_value_t = 5_000)]
+    ismcts_iterations: usize,
+
+    /// Milliseconds the ISMCTS player may spend on each move
+    #[arg(long)]
+    ismcts_time: Option<u64>,
+
+    /// Evaluate this player's hand in general (1 is the first player) by dealing the other
+    /// cards at random many times instead of playing the games
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=52),
+          conflicts_with_all = ["replay", "find_winner", "dot", "estimate", "hint", "humans"])]
+    evaluate: Option<u8>,
+
+    /// Number of random deals of the other cards when evaluating a hand
+    #[arg(long, default_value_t = 1_000, requires = "evaluate")]
+    deals: usize,
+
+    /// Number of random games to play for each deal when evaluating a hand
+    #[arg(long, default_value_t = 200, requires = "evaluate")]
+    deal_probes: usize,
+
+    /// Play this many single games from the deal, each player choosing their cards as they
+    /// go from what they can see, and show the wins for each player
+    #[arg(long, conflicts_with_all = ["replay", "find_winner", "dot", "estimate", "hint", "humans", "evaluate"])]
+    simulate: Option<usize>,
+
+    /// Play a tournament of this many random deals between the entrants, one for each
+    /// player, playing each deal once for every rotation of the entrants around the table.
+    /// The number of players is the number of entrants, and ISMCTS entrants are limited by
+    /// iterations rather than time so games can be replayed from their seed
+    #[arg(long, conflicts_with_all = ["player_count", "deck_hash", "deal_id", "hands", "position",
+          "no_shuffle", "replay", "find_winner", "dot", "estimate", "hint", "humans", "evaluate",
+          "simulate", "ismcts_time"])]
+    tournament: Option<usize>,
+
+    /// Player taking part in the tournament. Give once for each player (default is one of
+    /// each strategy)
+    #[arg(long = "entrant", value_enum, requires = "tournament")]
+    entrants: Vec<EntrantArg>,
+
+    /// Seed of the first tournament or hand evaluation deal, or of the random games estimating
+    /// the wins in a game tree DOT file (default is random). Each deal after it uses the next
+    /// seed, so a tournament of one deal from a logged seed replays that deal
+    #[arg(long, requires = "seeded")]
+    seed: Option<u64>,
+
+    /// Write each tournament game to a file, with its seed, deck hash and moves
+    #[arg(long, requires = "tournament")]
+    tournament_log: Option<String>,
+
+    /// Estimate the number of games and time to play them instead of playing them
+    #[arg(long)]
+    estimate: bool,
+
+    /// Number of random games to play when estimating, or when giving hints that can't
+    /// play every game in time
+    #[arg(long, default_value_t = 10_000)]
+    probes: usize,
+
+    /// Show the win rate for each card the player to move can play instead of playing
+    /// the games
+    #[arg(long, conflicts_with_all = ["replay", "find_winner", "dot", "estimate"])]
+    hint: bool,
+
+    /// Seconds to spend playing every game after each card when giving hints before
+    /// falling back to random games
+    #[arg(long, default_value_t = 5)]
+    hint_time: u64,
+
+    /// Show a full-screen dashboard of the results while playing (always uses the dfs
+    /// engine), or step through the game when replaying
+    #[cfg(feature = "tui")]
+    #[arg(long, conflicts_with_all = ["dot", "estimate", "hint", "humans", "simulate", "evaluate"])]
+    tui: bool,
+
+    /// Show every player's cards when stepping through a game in the terminal UI
+    #[cfg(feature = "tui")]
+    #[arg(long, requires = "tui")]
+    open: bool,
+}
+
+/// Time to spend measuring the rate games are played at when estimating
+const CALIBRATION_TIME: Duration = Duration::from_secs(2);
+
+#[derive(ValueEnum, Clone, Copy)]
+enum StrategyArg {
+    /// A single no consequence card is the preferred play, then in-sequence cards
+    NoConsequence,
+    /// A single no consequence card and in-sequence cards are the preferred play
+    Preferred,
+    /// All playable cards are considered equal
+    Dumb,
+    /// Cards are scored with the weights given by --weights and the best are played
+    Weighted,
 }
 
-#[tokio::main]
-async fn main() {
+#[derive(ValueEnum, Clone, Copy)]
+enum EntrantArg {
+    /// No consequence strategy
+    NoConsequence,
+    /// Preferred strategy
+    Preferred,
+    /// Dumb strategy
+    Dumb,
+    /// Weighted strategy with the next weights given by --weights
+    Weighted,
+    /// ISMCTS player playing games out with the chosen strategy
+    Ismcts,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum Engine {
+    /// Synchronous depth first search with a work-stealing thread pool
+    Dfs,
+    /// Recursive async search spawning tokio tasks
+    Tokio,
+}
+
+fn main() {
     let args = Args::parse();
 
-    // Create the card deck
-    let deck = if let Some(hash) = &args.deck_hash {
-        let deck = Deck::new_from_hash(hash);
+    // Create the tokio runtime and rayon thread pool
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    builder.enable_all();
 
-        if deck.is_none() {
-            println!("Card deck hash {hash} is not valid");
-            exit(1);
+    if let Some(threads) = args.threads {
+        builder.worker_threads(threads as usize);
+
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads as usize)
+            .build_global()
+            .expect("could not create thread pool");
+    }
+
+    builder
+        .build()
+        .expect("could not create tokio runtime")
+        .block_on(run(args));
+}
+
+async fn run(mut args: Args) {
+    let symbols = if args.ascii {
+        Symbols::Ascii
+    } else {
+        Symbols::Unicode
+    };
+
+    let strategy = match args.strategy {
+        StrategyArg::NoConsequence => Strategy::NoConsequence,
+        StrategyArg::Preferred => Strategy::Preferred,
+        StrategyArg::Dumb => Strategy::Dumb,
+        StrategyArg::Weighted => match args.weights.first() {
+            Some(weights) => Strategy::Weighted(load_weights(weights)),
+            None => {
+                println!("The weighted strategy needs --weights");
+                exit(1);
+            }
+        },
+    };
+
+    // Every weights given must be used by the strategy or a weighted entrant
+    let weighted_entrants = args
+        .entrants
+        .iter()
+        .filter(|e| matches!(e, EntrantArg::Weighted))
+        .count();
+    let weights_used =
+        weighted_entrants.max(usize::from(matches!(args.strategy, StrategyArg::Weighted)));
+
+    if args.weights.len() > weights_used {
+        if weights_used == 0 {
+            println!("--weights is only used by the weighted strategy or weighted entrants");
+        } else {
+            println!(
+                "Only {weights_used} --weights can be used but {} were given",
+                args.weights.len()
+            );
         }
+        exit(1);
+    }
+
+    let split = args
+        .split_depth
+        .map_or(Split::Adaptive, |d| Split::Depth(d as usize));
+
+    if !args.hands.is_empty() {
+        // One player for each hand
+        args.player_count = args.hands.len() as u8;
+    }
+
+    let hint_limits = HintLimits {
+        time: Duration::from_secs(args.hint_time),
+        probes: args.probes,
+    };
+
+    // Play a tournament between the entrants
+    if let Some(deals) = args.tournament {
+        let mut entrants = create_entrants(&args, strategy);
+
+        let config = TournamentConfig {
+            deals,
+            seed: args.seed.unwrap_or_else(rand::random),
+        };
+
+        println!(
+            "Playing {} deals with {} players from seed {}...",
+            deals.num_format(),
+            entrants.len(),
+            config.seed
+        );
+
+        let results = match &args.tournament_log {
+            Some(file) => {
+                let logged = fs::File::create(file).and_then(|f| {
+                    tournament_logged(&mut entrants, &config, &mut BufWriter::new(f))
+                });
+
+                match logged {
+                    Ok(results) => {
+                        println!("Games written to {file}");
+                        results
+                    }
+                    Err(e) => {
+                        println!("Failed to write tournament log {file}: {e}");
+                        exit(1);
+                    }
+                }
+            }
+            None => tournament(&mut entrants, &config, |_| ()),
+        };
+
+        results.print();
+        return;
+    }
 
-        deck.unwrap()
+    // Don't show everyone's cards when people are playing
+    let interactive = !args.humans.is_empty();
+
+    // Create the game state
+    let (state, start) = if let Some(position) = &args.position {
+        let position = load_position(position);
+
+        println!("Board:");
+        BoardLayout::new(position.board())
+            .symbols(symbols)
+            .print("  ");
+        println!("Player to move: {}", position.to_move() + 1);
+        println!(
+            "Missed goes: {}",
+            position
+                .passes()
+                .iter()
+                .map(|p| format!("{p}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+
+        let start = format!("Position:\n{position}");
+
+        (State::new_from_position(&position), start)
     } else {
-        let mut deck = Deck::new();
+        let deck = create_deck(&args);
 
-        // Shuffle the deck
-        if !args.no_shuffle {
-            deck.shuffle();
+        let deck_hash = deck.hash_string_checksummed();
+
+        if !interactive {
+            deck.iter().cloned().print("Card deck:", symbols);
+            println!("Card deck hash: {deck_hash}");
+            println!(
+                "Deal ID: {}",
+                DealId::new_from_deck(&deck, args.player_count)
+            );
         }
 
-        deck
+        let start = format!(
+            "Card deck hash: {deck_hash}\nPlayers: {}",
+            args.player_count
+        );
+
+        (State::new(args.player_count, deck), start)
     };
 
-    deck.iter().cloned().print("Card deck:");
-    println!("Card deck hash: {}", deck.hash_string());
+    let mut seats = create_seats(&args, state.player_count(), strategy);
 
-    // Play
-    let state = State::new(args.player_count, deck);
+    // Play a game interactively
+    if interactive {
+        let humans = seat_indexes(&args.humans, state.player_count());
+
+        if let Some(seat) = args.ismcts_seats.iter().find(|s| args.humans.contains(s)) {
+            println!("Seat {seat} can't be played by both a person and ISMCTS");
+            exit(1);
+        }
+
+        let mut seats = seats
+            .into_iter()
+            .enumerate()
+            .map(|(i, seat)| (!humans.contains(&i)).then_some(seat))
+            .collect::<Vec<_>>();
+
+        play_interactive(state, &mut seats, strategy, &hint_limits, symbols);
+
+        return;
+    }
 
     println!("Player cards:");
     state
         .all_player_cards()
         .iter()
         .enumerate()
-        .for_each(|(i, p)| p.card_iterator().print(&format!("  Player {}:", i + 1)));
+        .for_each(|(i, p)| {
+            p.card_iterator()
+                .print(&format!("  Player {}:", i + 1), symbols)
+        });
+
+    // Show the win rate for each card the player to move can play
+    if args.hint {
+        print_hints(&state, strategy, &hint_limits, symbols);
+        return;
+    }
+
+    // Evaluate a hand over random deals of the other cards
+    if let Some(seat) = args.evaluate {
+        let limits = EvaluateLimits {
+            deals: args.deals,
+            probes: args.deal_probes,
+        };
+
+        let player = seat_indexes(&[seat], state.player_count())[0];
+        let seed = args.seed.unwrap_or_else(rand::random);
+
+        println!(
+            "Evaluating player {}'s hand over {} deals of the other cards from seed {seed}...",
+            player + 1,
+            limits.deals.num_format()
+        );
+
+        println!("{}", evaluate_hand(&state, player, strategy, &limits, seed));
+        return;
+    }
+
+    // Play single games with the seats
+    if let Some(games) = args.simulate {
+        println!("Simulating {} games...", games.num_format());
+
+        simulate(state, &mut seats, games, &mut rand::thread_rng()).print();
+        return;
+    }
+
+    // Estimate the size of the game tree
+    if args.estimate {
+        println!(
+            "Estimating with {} random games...",
+            args.probes.num_format()
+        );
+
+        let estimate = estimate(state.clone(), strategy, args.probes);
+
+        // Time a short run to find the rate games are played at
+        estimate.print(games_per_second(state, strategy, split, CALIBRATION_TIME));
+        return;
+    }
+
+    // Export the game tree
+    if let Some(file) = &args.dot {
+        let limits = DotLimits {
+            depth: args.dot_depth,
+            nodes: args.dot_nodes,
+            probes: args.dot_probes,
+        };
+
+        let dot = export_dot(
+            state,
+            strategy,
+            &limits,
+            args.seed.unwrap_or_else(rand::random),
+        );
+
+        if let Err(e) = fs::write(file, dot) {
+            println!("Failed to write game tree to {file}: {e}");
+            exit(1);
+        }
+
+        println!("Game tree written to {file}");
+
+        return;
+    }
+
+    // Replay a single game
+    if args.replay.is_some() || args.find_winner.is_some() {
+        let line = if let Some(path) = &args.replay {
+            match replay(state, strategy, path) {
+                Ok(line) => line,
+                Err(e) => {
+                    println!("{e}");
+                    exit(1);
+                }
+            }
+        } else {
+            let filter = GameFilter {
+                winner: args
+                    .find_winner
+                    .map(|p| seat_indexes(&[p], state.player_count())[0]),
+                max_misses: args.find_max_misses,
+            };
+
+            match find(state, strategy, &filter) {
+                Some(line) => line,
+                None => {
+                    println!("No matching game found");
+                    exit(1);
+                }
+            }
+        };
+
+        #[cfg(feature = "tui")]
+        if args.tui {
+            tui::view_line(&line, args.open, symbols);
+        } else {
+            line.print(symbols);
+        }
+
+        #[cfg(not(feature = "tui"))]
+        line.print(symbols);
+
+        if let Some(file) = &args.export {
+            export(file, &start, &line);
+        }
+
+        return;
+    }
 
     println!("Playing games...");
 
+    // Stop when the time limit is reached
+    let time_limit = args.time_limit.map(Duration::from_secs);
+    let stop = time_limit.map_or_else(Stop::new, Stop::after);
+    stop_on_interrupt(&stop);
+
     let process_stats_start = ProcessStats::get()
         .await
         .expect("could not get stats for running process");
     let start = Instant::now();
 
-    let results = play(state, args.strategy).await;
+    let results = match args.engine {
+        #[cfg(feature = "tui")]
+        _ if args.tui => tui::watch(state, strategy, split, stop),
+        Engine::Dfs => play_dfs(state, strategy, split, stop),
+        Engine::Tokio => play(state, strategy, split, stop).await,
+    };
 
     let duration = start.elapsed();
     let process_stats_end = ProcessStats::get()
         .await
         .expect("could not get stats for running process");
 
+    if !results.is_complete() && time_limit.is_some_and(|limit| duration >= limit) {
+        println!("Time limit reached");
+    }
+
     println!("Time elapsed: {duration:?}");
     println!(
         "Process time: {:?} user, {:?} kernel",
@@ -92,5 +596,222 @@ async fn main() {
     );
 
     // Print results
-    results.print(&args);
+    results.print(strategy, symbols);
+}
+
+/// Creates a bot for each seat, using ISMCTS for the seats chosen and the strategy for
+/// the rest
+fn create_seats(args: &Args, player_count: usize, strategy: Strategy) -> Vec<Box<dyn Seat>> {
+    let ismcts_seats = seat_indexes(&args.ismcts_seats, player_count);
+
+    let limits = IsmctsLimits {
+        iterations: args.ismcts_iterations,
+        time: args.ismcts_time.map(Duration::from_millis),
+    };
+
+    (0..player_count)
+        .map(|i| -> Box<dyn Seat> {
+            if ismcts_seats.contains(&i) {
+                Box::new(Ismcts::new(limits.clone(), strategy))
+            } else {
+                Box::new(strategy)
+            }
+        })
+        .collect()
+}
+
+/// Converts seat numbers from the command line to player indexes, exiting if any are out
+/// of range
+fn seat_indexes(seats: &[u8], player_count: usize) -> Vec<usize> {
+    let indexes = seats.iter().map(|s| *s as usize - 1).collect::<Vec<_>>();
+
+    if let Some(seat) = indexes.iter().find(|s| **s >= player_count) {
+        println!("There is no seat {} with {player_count} players", seat + 1);
+        exit(1);
+    }
+
+    indexes
+}
+
+/// Creates the tournament entrants, by default one of each strategy
+fn create_entrants(args: &Args, strategy: Strategy) -> Vec<Box<dyn Seat>> {
+    let limits = IsmctsLimits {
+        iterations: args.ismcts_iterations,
+        time: args.ismcts_time.map(Duration::from_millis),
+    };
+
+    let entrants = if args.entrants.is_empty() {
+        vec![
+            EntrantArg::NoConsequence,
+            EntrantArg::Preferred,
+            EntrantArg::Dumb,
+        ]
+    } else {
+        args.entrants.clone()
+    };
+
+    if entrants.len() < 2 {
+        println!("A tournament needs at least two entrants");
+        exit(1);
+    }
+
+    // Weighted entrants take the weights in turn
+    let mut weights = args.weights.iter();
+
+    entrants
+        .into_iter()
+        .map(|entrant| -> Box<dyn Seat> {
+            match entrant {
+                EntrantArg::NoConsequence => Box::new(Strategy::NoConsequence),
+                EntrantArg::Preferred => Box::new(Strategy::Preferred),
+                EntrantArg::Dumb => Box::new(Strategy::Dumb),
+                EntrantArg::Weighted => match weights.next() {
+                    Some(w) => Box::new(Strategy::Weighted(load_weights(w))),
+                    None => {
+                        println!("Each weighted entrant needs its own --weights");
+                        exit(1);
+                    }
+                },
+                EntrantArg::Ismcts => Box::new(Ismcts::new(limits.clone(), strategy)),
+            }
+        })
+        .collect()
+}
+
+/// Stops the search when Ctrl-C is pressed, exiting immediately if it is pressed again
+fn stop_on_interrupt(stop: &Stop) {
+    let stop = stop.clone();
+    let interrupted = AtomicBool::new(false);
+
+    let result = ctrlc::set_handler(move || {
+        if interrupted.swap(true, Ordering::Relaxed) {
+            exit(130);
+        }
+
+        println!("Interrupted, stopping... (press Ctrl-C again to exit immediately)");
+        stop.stop();
+    });
+
+    if let Err(e) = result {
+        println!("Failed to set Ctrl-C handler: {e}");
+    }
+}
+
+fn create_deck(args: &Args) -> Deck {
+    if !args.hands.is_empty() {
+        let hands = args
+            .hands
+            .iter()
+            .enumerate()
+            .map(|(i, hand)| match hand.parse::<CardCollection>() {
+                Ok(cards) => cards,
+                Err(e) => {
+                    println!("Hand {} is not valid: {e}", i + 1);
+                    exit(1);
+                }
+            })
+            .collect::<Vec<_>>();
+
+        match Deck::new_from_hands(&hands) {
+            Ok(deck) => deck,
+            Err(e) => {
+                println!("{e}");
+                exit(1);
+            }
+        }
+    } else if let Some(id) = &args.deal_id {
+        match DealId::parse(id, args.player_count) {
+            Ok(deal_id) => deal_id.deck(),
+            Err(e) => {
+                println!("{e}");
+                exit(1);
+            }
+        }
+    } else if let Some(hash) = &args.deck_hash {
+        if hash.contains([' ', ',']) {
+            // List of cards
+            match hash.parse::<Deck>() {
+                Ok(deck) => return deck,
+                Err(e) => {
+                    println!("Card deck is not valid: {e}");
+                    exit(1);
+                }
+            }
+        }
+
+        match Deck::new_from_hash(hash) {
+            Ok(deck) => deck,
+            Err(e) => {
+                println!("Card deck hash {hash} is not valid: {e}");
+                exit(1);
+            }
+        }
+    } else {
+        let mut deck = Deck::new();
+
+        // Shuffle the deck
+        if !args.no_shuffle {
+            deck.shuffle();
+        }
+
+        deck
+    }
+}
+
+/// Loads a position from a file, or from the string itself if no such file exists
+fn load_position(position: &str) -> Position {
+    let string = if Path::new(position).is_file() {
+        match fs::read_to_string(position) {
+            Ok(string) => string,
+            Err(e) => {
+                println!("Failed to read position from {position}: {e}");
+                exit(1);
+            }
+        }
+    } else {
+        position.to_string()
+    };
+
+    match string.parse::<Position>() {
+        Ok(position) => position,
+        Err(e) => {
+            println!("Position is not valid: {e}");
+            exit(1);
+        }
+    }
+}
+
+/// Loads strategy weights from the file named, or from the string itself if there is no
+/// such file
+fn load_weights(weights: &str) -> Weights {
+    let string = if Path::new(weights).is_file() {
+        match fs::read_to_string(weights) {
+            Ok(string) => string,
+            Err(e) => {
+                println!("Failed to read weights from {weights}: {e}");
+                exit(1);
+            }
+        }
+    } else {
+        weights.to_string()
+    };
+
+    match string.parse::<Weights>() {
+        Ok(weights) => weights,
+        Err(e) => {
+            println!("Weights are not valid: {e}");
+            exit(1);
+        }
+    }
+}
+
+fn export(file: &str, start: &str, line: &GameLine) {
+    let contents = format!("{start}\nGame path: {}\n{line}\n", line.path());
+
+    if let Err(e) = fs::write(file, contents) {
+        println!("Failed to write game to {file}: {e}");
+        exit(1);
+    }
+
+    println!("Game written to {file}");
 }
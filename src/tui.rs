@@ -0,0 +1,395 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use numformat::NumFormat;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Gauge, List, ListState, Paragraph, Row, Table},
+    DefaultTerminal, Frame,
+};
+
+use sevens::{
+    cards::{BoardLayout, CardCollection, CellState, DisplaySymbols, Symbols},
+    game::{play_dfs_watched, GameLine, Move, Progress, Results, Split, State, Stop, Strategy},
+};
+
+/// Time between redraws of the dashboard
+const REFRESH: Duration = Duration::from_millis(250);
+
+/// Steps through a game line full-screen. Shows the board, the players' hands (card
+/// counts unless open is set), passes and the move log
+pub fn view_line(line: &GameLine, open: bool, symbols: Symbols) {
+    let mut viewer = LineViewer {
+        line,
+        open,
+        symbols,
+        step: 0,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = viewer.run(&mut terminal);
+    ratatui::restore();
+
+    result.expect("terminal error");
+}
+
+/// Plays all games from a state full-screen with a dashboard of the results so far.
+/// Returns the results when the search finishes or is stopped
+pub fn watch(state: State, strategy: Strategy, split: Split, stop: Stop) -> Results {
+    let players = state.player_count();
+    let progress = Progress::new(players as u8);
+
+    let search = {
+        let progress = progress.clone();
+        let stop = stop.clone();
+
+        thread::spawn(move || play_dfs_watched(state, strategy, split, stop, progress))
+    };
+
+    let mut dashboard = Dashboard {
+        strategy,
+        players,
+        progress,
+        stop,
+        start: Instant::now(),
+        elapsed: Duration::ZERO,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = dashboard.run(&mut terminal, &search);
+    ratatui::restore();
+
+    result.expect("terminal error");
+
+    search.join().expect("search thread panicked")
+}
+
+/// Replay viewer state
+struct LineViewer<'a> {
+    line: &'a GameLine,
+    open: bool,
+    symbols: Symbols,
+    /// Number of moves made
+    step: usize,
+}
+
+impl LineViewer<'_> {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        let moves = self.line.moves().len();
+
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+
+                match key.code {
+                    KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => {
+                        self.step = (self.step + 1).min(moves)
+                    }
+                    KeyCode::Left | KeyCode::Char('h') | KeyCode::Backspace => {
+                        self.step = self.step.saturating_sub(1)
+                    }
+                    KeyCode::PageDown => self.step = (self.step + 10).min(moves),
+                    KeyCode::PageUp => self.step = self.step.saturating_sub(10),
+                    KeyCode::Home => self.step = 0,
+                    KeyCode::End => self.step = moves,
+                    _ if is_quit(&key) => return Ok(()),
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let moves = self.line.moves();
+
+        // Play the moves up to the current step
+        let mut state = self.line.start().clone();
+        let mut passes = (0..state.player_count())
+            .map(|player| state.start_passes(player))
+            .collect::<Vec<_>>();
+
+        for m in &moves[..self.step] {
+            match m {
+                Move::Play(_, card) => state.play_card(card.clone()),
+                Move::Pass(player) => passes[*player] += 1,
+            }
+
+            state.next_player();
+        }
+
+        let finished = self.step == moves.len();
+        let next_player = moves.get(self.step).map(|m| match m {
+            Move::Play(player, _) | Move::Pass(player) => *player,
+        });
+
+        let [board_area, players_area, moves_area, help] = Layout::vertical([
+            Constraint::Length(6),
+            Constraint::Length(state.player_count() as u16 + 3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        // Board
+        frame.render_widget(
+            Paragraph::new(board_lines(state.board(), self.symbols)).block(
+                Block::bordered().title(format!(" Board - move {}/{} ", self.step, moves.len())),
+            ),
+            board_area,
+        );
+
+        // Players
+        let rows = state
+            .all_player_cards()
+            .iter()
+            .enumerate()
+            .map(|(player, cards)| {
+                let hand = if self.open {
+                    cards.display(self.symbols).to_string()
+                } else {
+                    format!("{} cards", cards.len())
+                };
+
+                let status = if finished && player == self.line.winner() {
+                    "Winner"
+                } else if next_player == Some(player) {
+                    "To move"
+                } else {
+                    ""
+                };
+
+                let row = Row::new(vec![
+                    format!("Player {}", player + 1),
+                    hand,
+                    passes[player].to_string(),
+                    status.to_string(),
+                ]);
+
+                if status.is_empty() {
+                    row
+                } else {
+                    row.style(Style::new().add_modifier(Modifier::BOLD))
+                }
+            });
+
+        frame.render_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Length(9),
+                    Constraint::Min(10),
+                    Constraint::Length(6),
+                    Constraint::Length(7),
+                ],
+            )
+            .header(Row::new(vec!["", "Hand", "Passes", ""]).underlined())
+            .block(Block::bordered().title(" Players ")),
+            players_area,
+        );
+
+        // Move log with the last move made selected
+        let items = moves
+            .iter()
+            .map(|m| m.display(self.symbols).to_string())
+            .collect::<Vec<_>>();
+        let mut list_state = ListState::default().with_selected(self.step.checked_sub(1));
+
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::bordered().title(" Moves "))
+                .highlight_style(Style::new().reversed())
+                .highlight_symbol("> "),
+            moves_area,
+            &mut list_state,
+        );
+
+        frame.render_widget(
+            Paragraph::new("←/→ step  PgUp/PgDn 10 moves  Home/End start/end  q quit").dim(),
+            help,
+        );
+    }
+}
+
+/// Results dashboard state
+struct Dashboard {
+    strategy: Strategy,
+    players: usize,
+    progress: Progress,
+    stop: Stop,
+    start: Instant,
+    /// Time taken when the search finished
+    elapsed: Duration,
+}
+
+impl Dashboard {
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        search: &thread::JoinHandle<Results>,
+    ) -> std::io::Result<()> {
+        loop {
+            let finished = search.is_finished();
+
+            if !finished {
+                self.elapsed = self.start.elapsed();
+            }
+
+            terminal.draw(|frame| self.draw(frame, finished))?;
+
+            if event::poll(REFRESH)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && is_quit(&key) {
+                        if finished {
+                            return Ok(());
+                        }
+
+                        // First ask the search to stop
+                        self.stop.stop();
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame, finished: bool) {
+        let results = self.progress.results();
+        let coverage = results.coverage().clamp(0.0, 1.0);
+        let secs = self.elapsed.as_secs_f64();
+
+        let status = match (finished, self.stop.is_stopped()) {
+            (true, false) => "Complete",
+            (true, true) => "Stopped",
+            (false, false) => "Running",
+            (false, true) => "Stopping",
+        };
+
+        let remaining = if !finished && coverage > 0.0 {
+            format!("{:.0}s", secs / coverage - secs)
+        } else {
+            "-".to_string()
+        };
+
+        let [summary_area, gauge_area, table_area, help_area] = Layout::vertical([
+            Constraint::Length(6),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let summary = vec![
+            Line::from(vec!["Status: ".into(), Span::from(status).bold()]),
+            Line::from(format!("Games played: {}", results.games().num_format())),
+            Line::from(format!(
+                "Elapsed: {secs:.1}s  Remaining: {remaining}  Games per second: {}",
+                ((results.games() as f64 / secs.max(1e-9)).round() as usize).num_format()
+            )),
+        ];
+
+        frame.render_widget(
+            Paragraph::new(summary).block(Block::bordered().title(format!(
+                " Sevens - {} players, {} strategy ",
+                self.players, self.strategy
+            ))),
+            summary_area,
+        );
+
+        frame.render_widget(
+            Gauge::default()
+                .block(Block::bordered().title(" Game tree covered "))
+                .gauge_style(Style::new().fg(Color::Green))
+                .ratio(coverage),
+            gauge_area,
+        );
+
+        // Player results
+        let games = results.games().max(1) as f64;
+
+        let rows = results
+            .player_results()
+            .iter()
+            .enumerate()
+            .map(|(player, p)| {
+                #[allow(unused_mut)]
+                let mut cells = vec![
+                    format!("Player {}", player + 1),
+                    p.wins().num_format(),
+                    format!("{:.2}%", p.wins() as f64 * 100.0 / games),
+                ];
+
+                #[cfg(not(feature = "nostats"))]
+                {
+                    cells.push(p.misses().num_format());
+                    cells.push(p.best_win().to_string());
+                }
+
+                Row::new(cells)
+            });
+
+        #[allow(unused_mut)]
+        let mut header = vec!["", "Wins", "Win %"];
+
+        #[cfg(not(feature = "nostats"))]
+        header.extend(["Missed goes", "Most missed in a win"]);
+
+        let widths = header
+            .iter()
+            .map(|h| Constraint::Length(h.len().max(14) as u16));
+
+        frame.render_widget(
+            Table::new(rows, widths)
+                .header(Row::new(header).underlined())
+                .block(Block::bordered().title(" Results ")),
+            table_area,
+        );
+
+        let help = if finished {
+            "q quit"
+        } else {
+            "q stop the search"
+        };
+
+        frame.render_widget(Paragraph::new(help).dim(), help_area);
+    }
+}
+
+/// Returns true for keys which quit or stop
+fn is_quit(key: &KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+        || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+}
+
+/// Returns the board layout as a styled line for each suit, with played cards on a white
+/// background and the cards which can be played next highlighted
+fn board_lines(board: &CardCollection, symbols: Symbols) -> Vec<Line<'static>> {
+    BoardLayout::new(board)
+        .cells()
+        .into_iter()
+        .map(|(suit, cells)| {
+            let mut spans = vec![Span::from(format!("{} ", suit.display(symbols)))];
+
+            spans.extend(cells.iter().map(|cell| {
+                let colour = cell.colour().parse().unwrap_or(Color::Reset);
+
+                match cell.state {
+                    CellState::Played => {
+                        Span::styled(cell.text(), Style::new().fg(colour).bg(Color::White))
+                    }
+                    CellState::Open => {
+                        Span::styled(cell.text(), Style::new().fg(colour).bg(Color::Yellow))
+                    }
+                    CellState::Empty => Span::from(" ".repeat(cell.text().len())),
+                }
+            }));
+
+            Line::from(spans)
+        })
+        .collect()
+}